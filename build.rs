@@ -4,14 +4,284 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
-use std::io::Result;
+use std::{env, fmt::Write, fs, io::Result, path::PathBuf};
+
+use prost::Message;
+
+/// The package of the API messages.
+const PACKAGE: &str = "darklake.v1";
 
 fn main() -> Result<()> {
     let protos = &["src/proto/darklake/v1/api.proto"];
     let includes = &["src/proto"];
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let descriptor_path = out_dir.join("darklake.v1.descriptor.bin");
 
     tonic_prost_build::configure()
         .build_server(false)
+        .file_descriptor_set_path(&descriptor_path)
         .compile_protos(protos, includes)?;
+
+    let descriptor = FileDescriptorSet::decode(fs::read(&descriptor_path)?.as_slice())?;
+    fs::write(
+        out_dir.join("darklake.v1.rules.rs"),
+        generate_rules(&descriptor),
+    )?;
+    println!("cargo:rerun-if-changed=src/proto");
     Ok(())
 }
+
+/// Generates the code checking the protoc-gen-validate rules of the API messages.
+///
+/// Every message with at least one rule gets an implementation of `ProtoRules`. The build fails
+/// on a rule that is not translated, so the client never silently skips a check of the server.
+fn generate_rules(descriptor: &FileDescriptorSet) -> String {
+    let mut code = String::from("// Generated by build.rs from the validate rules of api.proto.\n");
+    let mut patterns: Vec<String> = Vec::new();
+    for file in descriptor
+        .file
+        .iter()
+        .filter(|file| file.package.as_deref() == Some(PACKAGE))
+    {
+        for message in &file.message_type {
+            let mut checks = String::new();
+            for field in &message.field {
+                let Some(raw) = field
+                    .options
+                    .as_ref()
+                    .and_then(|options| options.rules.as_ref())
+                else {
+                    continue;
+                };
+                let name = &field.name;
+                let rules = FieldRules::decode(raw.as_slice()).expect("valid validate rules");
+                // The rules declared below are re-encoded as read, so a shorter encoding means
+                // that a rule unknown to this script was dropped.
+                if rules.encoded_len() != raw.len() {
+                    panic!(
+                        "{}.{name} has a validate rule that build.rs does not translate",
+                        message.name
+                    );
+                }
+                if let Some(rules) = &rules.string {
+                    if let Some(min_len) = rules.min_len {
+                        writeln!(
+                            checks,
+                            "            .min_len({name:?}, &self.{name}, {min_len})"
+                        )
+                        .unwrap();
+                    }
+                    if let Some(max_len) = rules.max_len {
+                        writeln!(
+                            checks,
+                            "            .max_chars({name:?}, &self.{name}, {max_len})"
+                        )
+                        .unwrap();
+                    }
+                    if let Some(pattern) = &rules.pattern {
+                        let index = patterns
+                            .iter()
+                            .position(|known| known == pattern)
+                            .unwrap_or_else(|| {
+                                patterns.push(pattern.clone());
+                                patterns.len() - 1
+                            });
+                        writeln!(
+                            checks,
+                            "            .pattern({name:?}, &self.{name}, &PATTERN_{index})"
+                        )
+                        .unwrap();
+                    }
+                }
+                if let Some(max_items) = rules.repeated.as_ref().and_then(|rules| rules.max_items) {
+                    writeln!(
+                        checks,
+                        "            .max_items({name:?}, &self.{name}, {max_items})"
+                    )
+                    .unwrap();
+                }
+                let optional = field.proto3_optional.unwrap_or(false);
+                if let Some(rules) = &rules.uint64 {
+                    write_bounds(
+                        &mut checks,
+                        name,
+                        optional,
+                        [rules.gt, rules.gte, rules.lt, rules.lte],
+                    );
+                }
+                if let Some(rules) = &rules.uint32 {
+                    write_bounds(
+                        &mut checks,
+                        name,
+                        optional,
+                        [rules.gt, rules.gte, rules.lt, rules.lte],
+                    );
+                }
+                if let Some(rules) = &rules.int32 {
+                    write_bounds(
+                        &mut checks,
+                        name,
+                        optional,
+                        [rules.gt, rules.gte, rules.lt, rules.lte],
+                    );
+                }
+            }
+            if checks.is_empty() {
+                continue;
+            }
+            write!(
+                code,
+                "\nimpl ProtoRules for crate::integrations_pb::{} {{\n    \
+                 fn check_rules(&self, validator: Validator) -> Validator {{\n        \
+                 validator\n{checks}    }}\n}}\n",
+                message.name
+            )
+            .unwrap();
+        }
+    }
+    for (index, pattern) in patterns.iter().enumerate() {
+        writeln!(
+            code,
+            "\nstatic PATTERN_{index}: std::sync::LazyLock<regex::Regex> =\n    \
+             std::sync::LazyLock::new(|| regex::Regex::new({pattern:?}).unwrap());"
+        )
+        .unwrap();
+    }
+    code
+}
+
+/// Writes the checks of the numeric bounds `[gt, gte, lt, lte]` of a field. The bounds of an
+/// optional field are only checked when it is set.
+fn write_bounds<T: std::fmt::Display>(
+    checks: &mut String,
+    name: &str,
+    optional: bool,
+    bounds: [Option<T>; 4],
+) {
+    for (check, bound) in ["gt", "gte", "lt", "lte"].into_iter().zip(bounds) {
+        if let Some(bound) = bound {
+            if optional {
+                writeln!(
+                    checks,
+                    "            .optional(self.{name}, |validator, value| \
+                     validator.{check}({name:?}, value, {bound}))"
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    checks,
+                    "            .{check}({name:?}, self.{name}, {bound})"
+                )
+                .unwrap();
+            }
+        }
+    }
+}
+
+// The subset of `google.protobuf.FileDescriptorSet` and of the protoc-gen-validate options read by
+// the build script. `prost-types` drops the extensions, so they are declared here.
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorSet {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<FileDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorProto {
+    #[prost(string, optional, tag = "2")]
+    package: Option<String>,
+    #[prost(message, repeated, tag = "4")]
+    message_type: Vec<DescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct DescriptorProto {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(message, repeated, tag = "2")]
+    field: Vec<FieldDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FieldDescriptorProto {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(message, optional, tag = "8")]
+    options: Option<FieldOptions>,
+    #[prost(bool, optional, tag = "17")]
+    proto3_optional: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FieldOptions {
+    /// The `(validate.rules)` extension, kept encoded to detect the rules not declared here.
+    #[prost(bytes = "vec", optional, tag = "1071")]
+    rules: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FieldRules {
+    #[prost(message, optional, tag = "3")]
+    int32: Option<Int32Rules>,
+    #[prost(message, optional, tag = "5")]
+    uint32: Option<UInt32Rules>,
+    #[prost(message, optional, tag = "6")]
+    uint64: Option<UInt64Rules>,
+    #[prost(message, optional, tag = "14")]
+    string: Option<StringRules>,
+    #[prost(message, optional, tag = "18")]
+    repeated: Option<RepeatedRules>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Int32Rules {
+    #[prost(int32, optional, tag = "2")]
+    lt: Option<i32>,
+    #[prost(int32, optional, tag = "3")]
+    lte: Option<i32>,
+    #[prost(int32, optional, tag = "4")]
+    gt: Option<i32>,
+    #[prost(int32, optional, tag = "5")]
+    gte: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct UInt32Rules {
+    #[prost(uint32, optional, tag = "2")]
+    lt: Option<u32>,
+    #[prost(uint32, optional, tag = "3")]
+    lte: Option<u32>,
+    #[prost(uint32, optional, tag = "4")]
+    gt: Option<u32>,
+    #[prost(uint32, optional, tag = "5")]
+    gte: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct UInt64Rules {
+    #[prost(uint64, optional, tag = "2")]
+    lt: Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    lte: Option<u64>,
+    #[prost(uint64, optional, tag = "4")]
+    gt: Option<u64>,
+    #[prost(uint64, optional, tag = "5")]
+    gte: Option<u64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct StringRules {
+    #[prost(uint64, optional, tag = "2")]
+    min_len: Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    max_len: Option<u64>,
+    #[prost(string, optional, tag = "6")]
+    pattern: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RepeatedRules {
+    #[prost(uint64, optional, tag = "2")]
+    max_items: Option<u64>,
+}
@@ -17,9 +17,9 @@ pub async fn create_base64_signed_transaction(
     unsigned_transaction: &str,
     keypair: &Keypair,
 ) -> Result<String> {
-    let transaction = deserialize_versioned_transaction(&unsigned_transaction)?;
+    let transaction = deserialize_versioned_transaction(unsigned_transaction)?;
     info!("Deserialized transaction: {:?}", transaction);
-    let signed_tx = sign_versioned_transaction(transaction, &[&keypair])?;
+    let signed_tx = sign_versioned_transaction(transaction, &[keypair])?;
     info!("Signed transaction: {:?}", signed_tx);
     let signed_tx_base64 = encode_versioned_transaction_to_base64(&signed_tx)?;
     Ok(signed_tx_base64)
@@ -9,9 +9,8 @@ mod common;
 use eyre::Result;
 
 use darklake_sdk_off_chain as sdk;
-use solana_sdk::signature::Keypair;
+use solana_sdk::{signature::Keypair, signer::Signer};
 use tracing::*;
-use tracing_subscriber;
 
 use crate::common::common::create_base64_signed_transaction;
 
@@ -25,30 +24,33 @@ async fn main() -> Result<()> {
     let config = sdk::Config::builder()
         .network(sdk::Network::Devnet)
         .url("http://localhost:50051")?
-        .is_final_url(true)
+        .final_url(true)
         .build()?;
 
-    let mut client = sdk::Client::new(config).await?;
+    let client = sdk::Client::new(config).await?;
 
     let secret: [u8; 32] = [
         73, 119, 63, 0, 6, 178, 215, 187, 108, 179, 236, 246, 77, 91, 48, 8, 92, 241, 232, 101,
         215, 54, 149, 16, 172, 166, 26, 71, 237, 30, 204, 226,
     ];
     let keypair = Keypair::new_from_array(secret);
-    // Wallet address for demo purposes only.
-    let wallet_address = "4bRZuVcTPFTFwcL9hgbEX3gdzQ37v2vr2GSmvR7X4Asp".to_string();
+    // The wallet address is the public key of the keypair signing the transactions.
+    let wallet_address: sdk::WalletAddress = keypair.pubkey().to_string().parse()?;
 
     let add_liquidity = client
-        .add_liquidity(sdk::AddLiquidityRequest {
-            token_mint_x: "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".to_string(),
-            token_mint_y: "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".to_string(),
-            user_address: wallet_address.clone(),
-            amount_lp: 20,
-            max_amount_x: 1000,
-            max_amount_y: 1000,
-            ref_code: "test".to_string(),
-            label: "test".to_string(),
-        })
+        .add_liquidity(
+            sdk::AddLiquidityRequest::builder(
+                wallet_address,
+                "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+                "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".parse()?,
+                20,
+                1000,
+                1000,
+            )
+            .ref_code("test")
+            .label("test")
+            .build()?,
+        )
         .await?;
 
     info!("Add liquidity: {:?}", add_liquidity);
@@ -58,16 +60,19 @@ async fn main() -> Result<()> {
     let _ = create_base64_signed_transaction(&add_liquidity.unsigned_transaction, &keypair).await?;
 
     let remove_liquidity = client
-        .remove_liquidity(sdk::RemoveLiquidityRequest {
-            token_mint_x: "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".to_string(),
-            token_mint_y: "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".to_string(),
-            user_address: wallet_address.clone(),
-            amount_lp: 20,
-            min_amount_x: 100,
-            min_amount_y: 100,
-            ref_code: "test".to_string(),
-            label: "test".to_string(),
-        })
+        .remove_liquidity(
+            sdk::RemoveLiquidityRequest::builder(
+                wallet_address,
+                "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+                "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".parse()?,
+                20,
+                100,
+                100,
+            )
+            .ref_code("test")
+            .label("test")
+            .build()?,
+        )
         .await?;
 
     info!("Remove liquidity: {:?}", remove_liquidity);
@@ -9,9 +9,8 @@ mod common;
 use eyre::Result;
 
 use darklake_sdk_off_chain as sdk;
-use solana_sdk::signature::Keypair;
+use solana_sdk::{signature::Keypair, signer::Signer};
 use tracing::*;
-use tracing_subscriber;
 
 use crate::common::common::create_base64_signed_transaction;
 
@@ -25,29 +24,32 @@ async fn main() -> Result<()> {
     let config = sdk::Config::builder()
         .network(sdk::Network::Devnet)
         .url("http://localhost:50051")?
-        .is_final_url(true)
+        .final_url(true)
         .build()?;
 
-    let mut client = sdk::Client::new(config).await?;
+    let client = sdk::Client::new(config).await?;
 
     let secret: [u8; 32] = [
         73, 119, 63, 0, 6, 178, 225, 187, 110, 179, 236, 246, 77, 91, 48, 8, 92, 241, 232, 101,
         215, 54, 149, 16, 172, 166, 236, 73, 237, 30, 204, 226,
     ];
     let keypair = Keypair::new_from_array(secret);
-    // Wallet address for demo purposes only.
-    let wallet_address = "4bRZeVcTPFTFwcL8hgbEX3udzQ37v5vr2GSmvR7X4Asp".to_string();
+    // The wallet address is the public key of the keypair signing the transactions.
+    let wallet_address: sdk::WalletAddress = keypair.pubkey().to_string().parse()?;
 
     let pool = client
-        .init_pool(sdk::InitPoolRequest {
-            token_mint_x: "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".to_string(),
-            token_mint_y: "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".to_string(),
-            user_address: wallet_address.clone(),
-            amount_x: 20,
-            amount_y: 20,
-            ref_code: "test".to_string(),
-            label: "test".to_string(),
-        })
+        .init_pool(
+            sdk::InitPoolRequest::builder(
+                wallet_address,
+                "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+                "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".parse()?,
+                20,
+                20,
+            )
+            .ref_code("test")
+            .label("test")
+            .build()?,
+        )
         .await?;
 
     info!("Init pool: {:?}", pool);
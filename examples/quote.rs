@@ -8,7 +8,6 @@ use eyre::Result;
 
 use darklake_sdk_off_chain as sdk;
 use tracing::*;
-use tracing_subscriber;
 
 /// Show how to get a quote from the Darklake DEX.
 ///
@@ -20,18 +19,21 @@ async fn main() -> Result<()> {
     let config = sdk::Config::builder()
         .network(sdk::Network::Devnet)
         .url("http://localhost:50051")?
-        .is_final_url(true)
+        .final_url(true)
         .build()?;
 
-    let mut client = sdk::Client::new(config).await?;
+    let client = sdk::Client::new(config).await?;
 
     let quote = client
-        .get_quote(sdk::QuoteRequest {
-            token_mint_x: "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".to_string(),
-            token_mint_y: "So11111111111111111111111111111111111111112".to_string(),
-            amount_in: 1000000000000000000,
-            is_swap_x_to_y: true,
-        })
+        .get_quote(
+            sdk::QuoteRequest::builder(
+                "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+                "So11111111111111111111111111111111111111112".parse()?,
+                1000000000000000000,
+            )
+            .is_swap_x_to_y(true)
+            .build()?,
+        )
         .await?;
 
     info!("Quote: {:?}", quote);
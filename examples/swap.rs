@@ -7,12 +7,11 @@
 mod common;
 
 use eyre::Result;
-use solana_sdk::signature::Keypair;
+use solana_sdk::{signature::Keypair, signer::Signer};
 
 use darklake_sdk_off_chain::{self as sdk, TradeStatus};
 use tokio::sync::mpsc;
 use tracing::*;
-use tracing_subscriber;
 
 use crate::common::common::create_base64_signed_transaction;
 
@@ -26,10 +25,10 @@ async fn main() -> Result<()> {
     let config = sdk::Config::builder()
         .network(sdk::Network::Devnet)
         .url("http://localhost:50051")?
-        .is_final_url(true)
+        .final_url(true)
         .build()?;
 
-    let mut client = sdk::Client::new(config).await?;
+    let client = sdk::Client::new(config).await?;
 
     // Generate keypair from wallet secrets, This secret key is for demo purposes only.
     let secret: [u8; 32] = [
@@ -37,17 +36,19 @@ async fn main() -> Result<()> {
         215, 54, 149, 16, 172, 166, 236, 71, 237, 32, 204, 226,
     ];
     let keypair = Keypair::new_from_array(secret);
-    // Wallet address for demo purposes only.
-    let wallet_address = "4bRZeVcTPPTFwcL8hgbEX3gdzQ37v2vr2GSmvR7X4Arp".to_string();
+    // The wallet address is the public key of the keypair signing the transactions.
+    let wallet_address: sdk::WalletAddress = keypair.pubkey().to_string().parse()?;
 
-    let request = sdk::CreateUnsignedTransactionRequest::builder(
-        &wallet_address,
-        "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX",
-        "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY",
+    // Quote the swap and accept at most 1% (100 basis points) less than the quoted output.
+    let quote_request = sdk::QuoteRequest::builder(
+        "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+        "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".parse()?,
         1000000,
-        100000,
     )
-    .build();
+    .build()?;
+    let request = client
+        .prepare_swap(quote_request, wallet_address, 100)
+        .await?;
 
     let response = client.create_unsigned_transaction(request).await?;
     let trade_id = response.trade_id;
@@ -7,21 +7,17 @@
 mod common;
 
 use eyre::Result;
-use solana_sdk::signature::Keypair;
+use solana_sdk::{signature::Keypair, signer::Signer};
 
-use darklake_sdk_off_chain::{
-    self as sdk, SendSignedTransactionAndCheckStatusRequest, SendSignedTransactionResponse,
-    TradeStatus,
-};
-use tokio::sync::mpsc;
+use darklake_sdk_off_chain::{self as sdk, SendSignedTransactionAndCheckStatusRequest, TradeEvent};
+use futures::StreamExt;
 use tracing::*;
-use tracing_subscriber;
 
 use crate::common::common::create_base64_signed_transaction;
 
 /// Show how to run a swap transaction using Darklake DEX.
 ///
-/// This example shows how to run a swap transaction using Darklake DEX. Instead of having two calls, one for sending the signed transaction and one for checking the status, this example shows how to do both in one call.
+/// This example shows how to run a swap transaction using Darklake DEX. Instead of having two calls, one for sending the signed transaction and one for checking the status, this example shows how to do both in one call and consume the progress as a stream of events.
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
@@ -29,10 +25,10 @@ async fn main() -> Result<()> {
     let config = sdk::Config::builder()
         .network(sdk::Network::Devnet)
         .url("http://localhost:50051")?
-        .is_final_url(true)
+        .final_url(true)
         .build()?;
 
-    let mut client = sdk::Client::new(config).await?;
+    let client = sdk::Client::new(config).await?;
 
     // Generate keypair from wallet secrets, This secret key is for demo purposes only.
     let secret: [u8; 32] = [
@@ -40,17 +36,17 @@ async fn main() -> Result<()> {
         215, 54, 149, 16, 178, 166, 238, 71, 237, 30, 204, 226,
     ];
     let keypair = Keypair::new_from_array(secret);
-    // Wallet address for demo purposes only.
-    let wallet_address = "4bRZeMcTPFTFwdL8hgbEX3gdzQ37v2vr2GSmvR0X4Bsp".to_string();
+    // The wallet address is the public key of the keypair signing the transactions.
+    let wallet_address: sdk::WalletAddress = keypair.pubkey().to_string().parse()?;
 
     let request = sdk::CreateUnsignedTransactionRequest::builder(
-        &wallet_address,
-        "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX",
-        "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY",
+        wallet_address,
+        "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+        "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".parse()?,
         1000000,
         100000,
     )
-    .build();
+    .build()?;
 
     let response = client.create_unsigned_transaction(request).await?;
     let trade_id = response.trade_id;
@@ -61,41 +57,33 @@ async fn main() -> Result<()> {
         create_base64_signed_transaction(&response.unsigned_transaction, &keypair).await?;
     // then the signed transaction should be sent to the Darklake DEX for the execution.
 
-    let (tx_signed, mut rx_signed) = mpsc::channel::<SendSignedTransactionResponse>(10);
-    let (tx_status, mut rx_status) = mpsc::channel::<TradeStatus>(10);
-
-    let request = SendSignedTransactionAndCheckStatusRequest::builder(
-        &signed_tx_base64,
-        &trade_id,
-        tx_signed,
-    )
-    .tx_status(tx_status)
-    .build();
-
-    let listener = tokio::spawn(async move {
-        info!("Listening for trade status updates...");
-        while let Some(status_update) = rx_status.recv().await {
-            info!("Received status update: {:?}", status_update);
+    let request =
+        SendSignedTransactionAndCheckStatusRequest::builder(&signed_tx_base64, &trade_id).build();
+
+    let mut events = client.send_signed_transaction_and_watch(request);
+    while let Some(event) = events.next().await {
+        match event? {
+            TradeEvent::Submitted(response) => {
+                info!("Received signed transaction response: {:?}", response);
+            }
+            TradeEvent::StatusChanged(transition) => {
+                info!(
+                    "Trade moved from {:?} to {:?} after {:?}",
+                    transition.from, transition.to, transition.elapsed
+                );
+            }
+            TradeEvent::Anomaly(transition) => {
+                warn!(
+                    "Unexpected transition from {:?} to {:?}",
+                    transition.from, transition.to
+                );
+            }
+            TradeEvent::Terminal { response, timings } => {
+                info!("Trade result: {:?}", response);
+                info!("Trade completed in {:?}", timings.total());
+            }
         }
-    });
-
-    let listener_signed = tokio::spawn(async move {
-        info!("Listening for signed transaction response...");
-        while let Some(signed_response) = rx_signed.recv().await {
-            info!(
-                "Received signed transaction response: {:?}",
-                signed_response
-            );
-        }
-    });
-
-    let trade_result = client
-        .send_signed_transaction_and_check_status(request)
-        .await?;
-
-    info!("Trade result: {:?}", trade_result);
+    }
 
-    listener.await?;
-    listener_signed.await?;
     Ok(())
 }
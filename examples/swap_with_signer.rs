@@ -0,0 +1,68 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::time::Duration;
+
+use eyre::Result;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use darklake_sdk_off_chain as sdk;
+use tracing::*;
+
+/// Show how to run a swap in one call, signed by the SDK.
+///
+/// This example shows how to run a swap with `Client::swap` and a `SolanaSigner`, without
+/// decoding and signing the transaction by hand. It requires the `solana` feature:
+/// `cargo run --example swap_with_signer --features solana`.
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = sdk::Config::builder()
+        .network(sdk::Network::Devnet)
+        .url("http://localhost:50051")?
+        .final_url(true)
+        .build()?;
+
+    let client = sdk::Client::new(config).await?;
+
+    // Generate keypair from wallet secrets, This secret key is for demo purposes only.
+    let secret: [u8; 32] = [
+        73, 119, 63, 0, 6, 178, 225, 187, 108, 176, 236, 246, 77, 91, 48, 8, 92, 241, 232, 101,
+        215, 54, 149, 16, 172, 166, 236, 71, 237, 32, 204, 226,
+    ];
+    let keypair = Keypair::new_from_array(secret);
+    let wallet_address: sdk::WalletAddress = keypair.pubkey().into();
+
+    let quote_request = sdk::QuoteRequest::builder(
+        "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+        "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY".parse()?,
+        1000000,
+    )
+    .build()?;
+    // Accept at most 1% (100 basis points) less than the quoted output.
+    // In production, replace `.skip_verification()` with `.verification_policy(...)` and the
+    // Darklake program ids of the network, so the transaction is checked against the request
+    // before it is signed.
+    let params = sdk::SwapParams::builder(quote_request, wallet_address, 100)
+        .timeout(Duration::from_secs(60))
+        .skip_verification()
+        .build();
+
+    let result = client
+        .swap(params, &sdk::SolanaSigner::new(keypair))
+        .await?;
+
+    info!(
+        "Trade {} ended with {:?}",
+        result.unsigned.trade_id, result.terminal.status
+    );
+    for transition in &result.transitions {
+        info!("{:?} -> {:?}", transition.from, transition.to);
+    }
+
+    Ok(())
+}
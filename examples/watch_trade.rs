@@ -0,0 +1,37 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use eyre::Result;
+use futures::StreamExt;
+
+use darklake_sdk_off_chain as sdk;
+use tracing::*;
+
+/// Show how to watch the status of a trade.
+///
+/// This example shows how to receive the status updates of a trade as a stream instead of polling.
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = sdk::Config::builder()
+        .network(sdk::Network::Devnet)
+        .url("http://localhost:50051")?
+        .final_url(true)
+        .build()?;
+
+    let client = sdk::Client::new(config).await?;
+
+    // Trade id returned by `create_unsigned_transaction`, for demo purposes only.
+    let trade_id = "2b0a6b0e-5c8a-4b4e-9d64-3f2a2f0b6a1c";
+
+    let mut updates = client.watch_trade(trade_id);
+    while let Some(update) = updates.next().await {
+        info!("Trade status: {:?}", update?.status);
+    }
+
+    Ok(())
+}
@@ -4,24 +4,89 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
-use std::time::Duration;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 use crate::{
-    core::config::Config,
+    core::signer::TransactionSigner,
+    core::{
+        config::Config,
+        retry::RetryPolicy,
+        store::{TradeRecord, TradeStore},
+    },
+    error::{Error, Result},
     models::{
-        AddLiquidityRequest, AddLiquidityResponse, CheckTradeStatusRequest,
-        CheckTradeStatusResponse, CreateUnsignedTransactionRequest,
+        AddLiquidityRequest, AddLiquidityResponse, CheckTradeStatusBatchRequest,
+        CheckTradeStatusRequest, CheckTradeStatusResponse, CreateUnsignedTransactionRequest,
         CreateUnsignedTransactionResponse, GetTradesListByUserRequest, GetTradesListByUserResponse,
-        InitPoolRequest, InitPoolResponse, QuoteRequest, QuoteResponse, RemoveLiquidityRequest,
-        RemoveLiquidityResponse, SendSignedTransactionAndCheckStatusRequest,
-        SendSignedTransactionRequest, SendSignedTransactionResponse, TradeStatus,
+        InitPoolRequest, InitPoolResponse, PollStrategy, QuoteRequest, QuoteResponse,
+        RemoveLiquidityRequest, RemoveLiquidityResponse,
+        SendSignedTransactionAndCheckStatusRequest, SendSignedTransactionRequest,
+        SendSignedTransactionResponse, SwapParams, SwapResult, TradeEvent, TradeStatus, Validate,
+        WalletAddress, swap::Verification,
     },
 };
-use eyre::Result;
+use futures::{
+    StreamExt,
+    stream::{self, BoxStream},
+};
 use tokio::sync::mpsc;
+use tonic::Code;
 use tracing::*;
 pub mod grpc_client;
+#[cfg(test)]
+pub(crate) mod mock;
 pub mod service;
+pub mod tracker;
+pub mod watch;
+
+/// The maximum number of concurrent checks when the batch call is not supported by the server.
+const MAX_CONCURRENT_CHECKS: usize = 16;
+
+/// The maximum number of trade ids of a batch call, as declared in `api.proto`.
+const MAX_BATCH_SIZE: usize = 100;
+
+/// The maximum number of times `Client::swap` re-creates a trade whose blockhash expired while
+/// the transaction was being signed.
+const MAX_BLOCKHASH_REFRESHES: u32 = 3;
+
+/// Stream of trade status updates.
+///
+/// The stream ends after the trade reaches a terminal status or after the first error.
+pub type TradeStatusStream = BoxStream<'static, Result<CheckTradeStatusResponse>>;
+
+/// Stream of trade events.
+///
+/// The stream ends after `TradeEvent::Terminal` or after the first error.
+pub type TradeEventStream = BoxStream<'static, Result<TradeEvent>>;
+
+/// Resolves the poll strategy of a request, falling back to a fixed interval.
+fn poll_strategy(
+    poll_strategy: &Option<PollStrategy>,
+    interval_millis: Option<u64>,
+) -> PollStrategy {
+    match (poll_strategy, interval_millis) {
+        (Some(poll_strategy), _) => poll_strategy.clone(),
+        (None, Some(interval_millis)) => {
+            PollStrategy::Fixed(Duration::from_millis(interval_millis))
+        }
+        (None, None) => PollStrategy::default(),
+    }
+}
+
+/// Forwards a value to an optional channel, ignoring a dropped receiver.
+async fn forward<T>(tx: &Option<mpsc::Sender<T>>, value: T, name: &str) {
+    if let Some(tx) = tx
+        && tx.send(value).await.is_err()
+    {
+        debug!("{} receiver dropped, the value is not forwarded", name);
+    }
+}
 
 /// Client type
 ///
@@ -35,8 +100,19 @@ pub enum ClientType {
 /// Client for the Darklake Integrations service.
 ///
 /// This struct is used to create a client for the Darklake Integrations service.
+///
+/// The client is cheap to clone and every method takes `&self`, so a single instance can be
+/// shared between tasks. Concurrent calls are multiplexed over the same underlying connection.
+#[derive(Clone)]
 pub struct Client {
-    service: Box<dyn service::Service>,
+    service: Arc<dyn service::Service>,
+    trade_store: Option<Arc<dyn TradeStore>>,
+    /// The retry policy, also used to resubscribe to an interrupted trade status stream.
+    retry_policy: RetryPolicy,
+    /// Set once the server answered `Unimplemented` to a batch call.
+    batch_unsupported: Arc<AtomicBool>,
+    /// Set once the server answered `Unimplemented` to a trade status stream.
+    streaming_unsupported: Arc<AtomicBool>,
 }
 
 impl Client {
@@ -50,13 +126,28 @@ impl Client {
     pub async fn new(config: Config) -> Result<Self> {
         debug!("Creating client with config: {:?}", config);
         let service = match config.client_type {
-            ClientType::Grpc => Box::new(
-                grpc_client::DarklakeIntegrationsClient::new(config.url.to_string())
-                    .await
-                    .map_err(|e| eyre::eyre!("Failed to create gRPC client: {}", e))?,
-            ),
+            ClientType::Grpc => {
+                Arc::new(grpc_client::DarklakeIntegrationsClient::new(&config).await?)
+            }
         };
-        Ok(Self { service })
+        Ok(Self {
+            service,
+            trade_store: config.trade_store,
+            retry_policy: config.retry_policy,
+            batch_unsupported: Arc::new(AtomicBool::new(false)),
+            streaming_unsupported: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Appends a record to the trade journal, if a store is configured.
+    ///
+    /// A failure of the store is logged and does not fail the call that produced the record.
+    pub(crate) async fn journal(&self, record: TradeRecord) {
+        if let Some(trade_store) = &self.trade_store
+            && let Err(e) = trade_store.append(record).await
+        {
+            warn!("Failed to record the trade in the trade store: {}", e);
+        }
     }
 
     /// Gets a quote from the Darklake Integrations service.
@@ -66,11 +157,13 @@ impl Client {
     /// # Errors
     ///
     /// Returns an error if the quote cannot be retrieved.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     ///
     /// # Returns
     ///
     /// Returns the `QuoteResponse` instance.
-    pub async fn get_quote(&mut self, request: QuoteRequest) -> Result<QuoteResponse> {
+    pub async fn get_quote(&self, request: QuoteRequest) -> Result<QuoteResponse> {
+        request.validate()?;
         self.service.quote(request).await
     }
 
@@ -81,11 +174,207 @@ impl Client {
     /// # Errors
     ///
     /// Returns an error if the unsigned transaction cannot be created.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     pub async fn create_unsigned_transaction(
-        &mut self,
+        &self,
         request: CreateUnsignedTransactionRequest,
     ) -> Result<CreateUnsignedTransactionResponse> {
-        self.service.create_unsigned_transaction(request).await
+        request.validate()?;
+        let response = self.service.create_unsigned_transaction(request).await?;
+        self.journal(TradeRecord::created(&response)).await;
+        Ok(response)
+    }
+
+    /// Refresh unsigned transaction
+    ///
+    /// This is used to re-create the trade of a request whose unsigned transaction can no longer
+    /// be sent because its recent blockhash expired, e.g. when
+    /// `CreateUnsignedTransactionResponse::is_blockhash_expired` or
+    /// `SendSignedTransactionResponse::is_blockhash_expired` is true. The trade is created again
+    /// with the same parameters, including the tracking id, and the new unsigned transaction
+    /// must be signed again. The expired trade is journaled as abandoned when a trade store is
+    /// configured, so it is not resumed.
+    ///
+    /// The `min_out` of the request is kept. If it was derived from a quote, which may be too old
+    /// by now, get a new quote and build a new request with `from_quote` instead, as `swap` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the unsigned transaction cannot be created.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `CreateUnsignedTransactionResponse` instance of the new trade.
+    pub async fn refresh_unsigned_transaction(
+        &self,
+        request: &CreateUnsignedTransactionRequest,
+        expired: &CreateUnsignedTransactionResponse,
+    ) -> Result<CreateUnsignedTransactionResponse> {
+        debug!(
+            "Refreshing the unsigned transaction of tracking id {}",
+            request.tracking_id
+        );
+        self.journal(TradeRecord::abandoned(&expired.trade_id))
+            .await;
+        self.create_unsigned_transaction(request.clone()).await
+    }
+
+    /// Prepare swap
+    ///
+    /// This is used to get a fresh quote and build the unsigned transaction request of its swap,
+    /// with `min_out` computed from the quoted output and the slippage tolerance, in basis points.
+    /// See `CreateUnsignedTransactionRequest::from_quote`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the quote cannot be retrieved.
+    /// Returns `Error::Validation` if the quote request or the resulting request is invalid.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `CreateUnsignedTransactionRequest` instance, to pass to
+    /// `create_unsigned_transaction`.
+    pub async fn prepare_swap(
+        &self,
+        request: QuoteRequest,
+        user_address: WalletAddress,
+        slippage_bps: u16,
+    ) -> Result<CreateUnsignedTransactionRequest> {
+        let quote = self.get_quote(request).await?;
+        CreateUnsignedTransactionRequest::from_quote(&quote, user_address, slippage_bps).build()
+    }
+
+    /// Swap
+    ///
+    /// This is used to run a whole swap in one call: get a fresh quote, create the unsigned
+    /// transaction with `min_out` derived from the slippage tolerance, sign it with the signer,
+    /// send it and follow the trade until it completes.
+    /// The same tracking id is used for every call, and the steps are journaled when a trade
+    /// store is configured, so an interrupted swap can be resumed with `resume_pending_trades`.
+    /// If the blockhash of the unsigned transaction is estimated to be expired once signed, or the
+    /// service reports it expired when sending the transaction, a new quote is requested and the
+    /// trade is re-created from it and signed again, up to 3 times.
+    ///
+    /// The unsigned transaction is verified against the verification policy of the params before
+    /// it is signed, unless the params explicitly skip the verification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any step fails, e.g. `Error::Verification` if the params have neither a
+    /// verification policy nor skip the verification, or if the unsigned transaction does not
+    /// match the policy, `Error::Signing` if the signer fails, `Error::BlockhashExpired` if the
+    /// blockhash is still expired after the last refresh, `Error::SendFailed` if the service
+    /// cannot send the transaction for another reason, or `Error::WatchStopped` if the deadline
+    /// expires before the trade completes.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `SwapResult` instance, with the terminal status and every intermediate artifact.
+    pub async fn swap<S>(&self, params: SwapParams, signer: &S) -> Result<SwapResult>
+    where
+        S: TransactionSigner + ?Sized,
+    {
+        if let Verification::Unset = params.verification {
+            return Err(Error::Verification(
+                "No verification policy is set, use SwapParamsBuilder::skip_verification to sign \
+                 without verification"
+                    .to_string(),
+            ));
+        }
+        let quote_request = QuoteRequest {
+            tracking_id: params.tracking_id.clone(),
+            ..params.quote_request
+        };
+        let build_request = |quote: &QuoteResponse| {
+            let mut builder = CreateUnsignedTransactionRequest::from_quote(
+                quote,
+                params.user_address,
+                params.slippage_bps,
+            )
+            .tracking_id(&params.tracking_id);
+            builder.priority_fee_micro_lamports = params.priority_fee_micro_lamports;
+            builder.compute_unit_limit = params.compute_unit_limit;
+            builder.priority_level = params.priority_level;
+            builder.build()
+        };
+        let mut quote = self.get_quote(quote_request.clone()).await?;
+        let mut request = build_request(&quote)?;
+        let mut unsigned = self.create_unsigned_transaction(request.clone()).await?;
+        let mut refreshes = 0;
+        let (signed_transaction, submitted) = loop {
+            #[cfg(feature = "solana")]
+            if let Verification::Policy(policy) = &params.verification {
+                crate::core::verify::verify_unsigned_swap(&request, &unsigned, policy)?;
+            }
+            let signed_transaction = signer.sign(&unsigned.unsigned_transaction).await?;
+            if !unsigned.is_blockhash_expired() {
+                let send_request =
+                    SendSignedTransactionRequest::builder(&signed_transaction, &unsigned.trade_id)
+                        .tracking_id(&params.tracking_id)
+                        .build();
+                let submitted = self.send_signed_transaction(send_request).await?;
+                if submitted.success {
+                    break (signed_transaction, submitted);
+                }
+                if !submitted.is_blockhash_expired() {
+                    return Err(Error::SendFailed {
+                        trade_id: submitted.trade_id,
+                        error_logs: submitted.error_logs,
+                    });
+                }
+            }
+            self.journal(TradeRecord::abandoned(&unsigned.trade_id))
+                .await;
+            if refreshes == MAX_BLOCKHASH_REFRESHES {
+                return Err(Error::BlockhashExpired { refreshes });
+            }
+            refreshes += 1;
+            // The price may have moved while signing, so `min_out` is derived from a new quote.
+            debug!(
+                "Blockhash expired, re-creating the trade of tracking id {}",
+                params.tracking_id
+            );
+            quote = self.get_quote(quote_request.clone()).await?;
+            request = build_request(&quote)?;
+            unsigned = self.create_unsigned_transaction(request.clone()).await?;
+        };
+
+        let mut builder =
+            CheckTradeStatusRequest::builder(&unsigned.trade_id).tracking_id(&params.tracking_id);
+        builder.deadline = params.deadline;
+        builder.cancellation_token = params.cancellation_token;
+        builder.poll_strategy = params.poll_strategy;
+        let status_request = builder.build();
+        let options = watch::WatchOptions {
+            poll_strategy: poll_strategy(&status_request.poll_strategy, None),
+            max_attempts: None,
+        };
+        let mut events = watch::watch_trade_events(self.clone(), status_request, options);
+
+        let mut transitions = Vec::new();
+        while let Some(event) = events.next().await {
+            match event? {
+                // The transaction was sent above, the watcher only reports the status updates.
+                TradeEvent::Submitted(_) => {}
+                TradeEvent::StatusChanged(transition) | TradeEvent::Anomaly(transition) => {
+                    transitions.push(transition);
+                }
+                TradeEvent::Terminal { response, timings } => {
+                    return Ok(SwapResult {
+                        quote,
+                        request,
+                        unsigned,
+                        signed_transaction,
+                        submitted,
+                        transitions,
+                        terminal: response,
+                        timings,
+                    });
+                }
+            }
+        }
+        Err(Error::StreamEnded)
     }
 
     /// Sends a signed transaction to the Darklake Integrations service.
@@ -95,39 +384,116 @@ impl Client {
     /// # Errors
     ///
     /// Returns an error if the signed transaction cannot be sent.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     ///
     /// # Returns
     ///
     /// Returns the `SendSignedTransactionResponse` instance.
     pub async fn send_signed_transaction(
-        &mut self,
+        &self,
         request: SendSignedTransactionRequest,
     ) -> Result<SendSignedTransactionResponse> {
-        self.service.send_signed_transaction(request).await
+        request.validate()?;
+        let response = self.service.send_signed_transaction(request).await?;
+        self.journal(TradeRecord::submitted(&response)).await;
+        Ok(response)
     }
 
     /// Check trade status
     ///
     /// This is used to check the status of a trade. This function checks just one time so it needs to be put in a loop to check the status until it is confirmed.
+    /// The deadline and cancellation token of the request are only used by the watchers.
     ///
     /// # Errors
     ///
     /// Returns an error if the trade status cannot be checked.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     ///
     /// # Returns
     ///
     /// Returns the `CheckTradeStatusResponse` instance.
     pub async fn check_trade_status(
-        &mut self,
+        &self,
         request: CheckTradeStatusRequest,
     ) -> Result<CheckTradeStatusResponse> {
+        request.validate()?;
         self.service.check_trade_status(request).await
     }
 
+    /// Check trade statuses
+    ///
+    /// This is used to check the status of several trades in one round trip, or one per 100
+    /// trades. If the server does not support the batch call, the trades are checked one by one,
+    /// concurrently, and the batch call is not tried again by this client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch call fails as a whole. The failure of a single trade is
+    /// reported in its own result.
+    ///
+    /// # Returns
+    ///
+    /// Returns one `CheckTradeStatusResponse` or error per trade id, in the same order.
+    pub async fn check_trade_statuses(
+        &self,
+        trade_ids: &[&str],
+    ) -> Result<Vec<Result<CheckTradeStatusResponse>>> {
+        if trade_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if !self.batch_unsupported.load(Ordering::Relaxed) {
+            match self.check_trade_status_batches(trade_ids).await {
+                Err(e) if e.code() == Some(Code::Unimplemented) => {
+                    debug!(
+                        "Trade status batches are not supported by the server, checking each trade"
+                    );
+                    self.batch_unsupported.store(true, Ordering::Relaxed);
+                }
+                result => return result,
+            }
+        }
+        let requests: Vec<CheckTradeStatusRequest> = trade_ids
+            .iter()
+            .map(|trade_id| CheckTradeStatusRequest::builder(trade_id).build())
+            .collect();
+        Ok(stream::iter(requests)
+            .map(|request| {
+                let client = self.clone();
+                async move { client.check_trade_status(request).await }
+            })
+            .buffered(MAX_CONCURRENT_CHECKS)
+            .collect()
+            .await)
+    }
+
+    /// Checks the statuses with batch calls of at most `MAX_BATCH_SIZE` trades.
+    async fn check_trade_status_batches(
+        &self,
+        trade_ids: &[&str],
+    ) -> Result<Vec<Result<CheckTradeStatusResponse>>> {
+        let mut results = Vec::with_capacity(trade_ids.len());
+        for trade_ids in trade_ids.chunks(MAX_BATCH_SIZE) {
+            let request = CheckTradeStatusBatchRequest::new(trade_ids);
+            request.validate()?;
+            results.extend(self.service.check_trade_status_batch(request).await?);
+        }
+        Ok(results)
+    }
+
     /// Check trade status loop
     ///
     /// This is used to check the status of a trade. This function checks until the trade is completed.
-    /// It also sends the status of the trade to the channel if provided.
+    /// It also sends the status of the trade to the channel if provided, once per status change.
+    /// A dropped receiver does not interrupt the loop.
+    ///
+    /// The status is streamed by the server when supported, otherwise it is polled following the
+    /// poll strategy of the request, or every `interval_millis` (500 by default) if it has none,
+    /// at most `max_attempts` times after the first check. `max_attempts` only applies to the
+    /// polling: an interrupted stream is opened again following the retry policy of the client.
+    ///
+    /// The loop stops early when the deadline of the request expires or its cancellation token is
+    /// cancelled. In that case, and when `max_attempts` is reached, it returns
+    /// `Error::WatchStopped` with the last observed status.
     ///
     /// # Errors
     ///
@@ -137,46 +503,63 @@ impl Client {
     ///
     /// Returns the `CheckTradeStatusResponse` instance.
     pub async fn check_trade_status_loop(
-        &mut self,
+        &self,
         request: CheckTradeStatusRequest,
         tx: Option<mpsc::Sender<TradeStatus>>,
         interval_millis: Option<u64>,
         max_attempts: Option<u32>,
     ) -> Result<CheckTradeStatusResponse> {
-        let mut attempts = 0;
-        let interval = interval_millis.unwrap_or(500);
-        let interval_check = Duration::from_millis(interval);
-        loop {
-            let tx = tx.clone();
-            let response = self.check_trade_status(request.clone()).await?;
-            if tx.is_some() {
-                if tx.unwrap().send(response.status.clone()).await.is_err() {
-                    return Err(eyre::eyre!("Receiver channel closed"));
-                }
-            }
-            match response.status {
-                TradeStatus::Confirmed => {}
-                TradeStatus::Settled => {
-                    return Ok(response);
-                }
-                TradeStatus::Failed => {
-                    return Ok(response);
-                }
-                TradeStatus::Cancelled => {
-                    return Ok(response);
-                }
-                TradeStatus::Unsigned => {}
-                TradeStatus::Signed => {}
-                TradeStatus::Slashed => {
-                    return Ok(response);
-                }
-            }
-            if max_attempts.is_some() && attempts >= max_attempts.unwrap() {
-                return Err(eyre::eyre!("Max attempts reached"));
+        let options = watch::WatchOptions {
+            poll_strategy: poll_strategy(&request.poll_strategy, interval_millis),
+            max_attempts,
+        };
+        let mut updates = watch::watch_trade_status(self.clone(), request, options);
+        while let Some(response) = updates.next().await {
+            let response = response?;
+            forward(&tx, response.status.clone(), "Trade status").await;
+            if response.status.is_terminal() {
+                return Ok(response);
             }
-            attempts += 1;
-            tokio::time::sleep(interval_check).await;
         }
+        Err(Error::StreamEnded)
+    }
+
+    /// Watch trade
+    ///
+    /// This is used to receive the status updates of a trade as a stream, without polling.
+    /// If the server does not support streaming, the status is polled every 500 milliseconds instead,
+    /// or following the poll strategy of the request for `watch_trade_status`, and streaming is
+    /// not tried again by this client. A stream interrupted by a transient failure is opened
+    /// again following the retry policy of the client.
+    /// Only status changes are yielded.
+    ///
+    /// The stream ends after the trade reaches a terminal status (`Settled`, `Slashed`,
+    /// `Cancelled` or `Failed`) or after the first error.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `TradeStatusStream` instance.
+    pub fn watch_trade(&self, trade_id: &str) -> TradeStatusStream {
+        self.watch_trade_status(CheckTradeStatusRequest::builder(trade_id).build())
+    }
+
+    /// Watch trade status
+    ///
+    /// This is used to receive the status updates of a trade as a stream, like `watch_trade`,
+    /// with the tracking id, deadline and cancellation token of the request.
+    ///
+    /// When the deadline expires or the token is cancelled, the stream yields
+    /// `Error::WatchStopped` with the last observed status, then ends.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `TradeStatusStream` instance.
+    pub fn watch_trade_status(&self, request: CheckTradeStatusRequest) -> TradeStatusStream {
+        let options = watch::WatchOptions {
+            poll_strategy: poll_strategy(&request.poll_strategy, None),
+            max_attempts: None,
+        };
+        watch::watch_trade_status(self.clone(), request, options)
     }
 
     /// Get trades list by user
@@ -186,72 +569,139 @@ impl Client {
     /// # Errors
     ///
     /// Returns an error if the trades list cannot be retrieved.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     ///
     /// # Returns
     ///
     /// Returns the `GetTradesListByUserResponse` instance.
     pub async fn get_trades_list_by_user(
-        &mut self,
+        &self,
         request: GetTradesListByUserRequest,
     ) -> Result<GetTradesListByUserResponse> {
+        request.validate()?;
         self.service.get_trades_list_by_user(request).await
     }
 
+    /// Send signed transaction and watch
+    ///
+    /// This is used to send a signed transaction and follow the trade until it completes, as a
+    /// stream of `TradeEvent`s that composes with `StreamExt`.
+    /// The channels of the request are ignored, see `send_signed_transaction_and_check_status`.
+    ///
+    /// The stream yields `TradeEvent::Submitted` first, then `TradeEvent::StatusChanged` for every
+    /// status transition and ends with `TradeEvent::Terminal`, or after the first error.
+    /// If the service could not send the transaction, the stream only yields
+    /// `Error::BlockhashExpired` or `Error::SendFailed`, and the trade is not watched.
+    /// When the deadline of the request expires or its cancellation token is cancelled, the stream
+    /// yields `Error::WatchStopped` with the last observed status.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `TradeEventStream` instance.
+    pub fn send_signed_transaction_and_watch(
+        &self,
+        request: SendSignedTransactionAndCheckStatusRequest,
+    ) -> TradeEventStream {
+        let options = watch::WatchOptions {
+            poll_strategy: poll_strategy(&request.poll_strategy, request.interval_millis),
+            max_attempts: request.max_attempts,
+        };
+        watch::submit_and_watch(self.clone(), request, options)
+    }
+
+    /// Send signed transaction and check status
+    ///
+    /// This is used to send a signed transaction and wait until the trade completes.
+    /// It is an adapter over `send_signed_transaction_and_watch` that forwards the events to the
+    /// channels of the request, if provided. A dropped receiver does not interrupt the call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signed transaction cannot be sent or the trade status cannot be checked.
+    ///
+    /// # Returns
+    ///
+    /// Returns the terminal `CheckTradeStatusResponse` instance.
     pub async fn send_signed_transaction_and_check_status(
-        &mut self,
+        &self,
         request: SendSignedTransactionAndCheckStatusRequest,
     ) -> Result<CheckTradeStatusResponse> {
-        let signed_response = self
-            .service
-            .send_signed_transaction(request.clone().into())
-            .await?;
-        if request
-            .tx_response
-            .send(signed_response.clone())
-            .await
-            .is_err()
-        {
-            return Err(eyre::eyre!(
-                "Signed transaction response receiver channel closed"
-            ));
-        }
-        let mut attempts = 0;
-        let interval = request.interval_millis.unwrap_or(500);
-        let interval_check = Duration::from_millis(interval);
-        loop {
-            let tx = request.tx_status.clone();
-            let response = self
-                .service
-                .check_trade_status(request.clone().into())
-                .await?;
-            if tx.is_some() {
-                if tx.unwrap().send(response.status.clone()).await.is_err() {
-                    return Err(eyre::eyre!("Trade status receiver channel closed"));
+        let tx_response = request.tx_response.clone();
+        let tx_status = request.tx_status.clone();
+        let mut events = self.send_signed_transaction_and_watch(request);
+        while let Some(event) = events.next().await {
+            match event? {
+                TradeEvent::Submitted(response) => {
+                    forward(&tx_response, response, "Signed transaction response").await;
                 }
-            }
-            match response.status {
-                TradeStatus::Confirmed => {}
-                TradeStatus::Settled => {
-                    return Ok(response);
-                }
-                TradeStatus::Failed => {
-                    return Ok(response);
+                TradeEvent::StatusChanged(transition) | TradeEvent::Anomaly(transition) => {
+                    forward(&tx_status, transition.to, "Trade status").await;
                 }
-                TradeStatus::Cancelled => {
-                    return Ok(response);
-                }
-                TradeStatus::Unsigned => {}
-                TradeStatus::Signed => {}
-                TradeStatus::Slashed => {
+                TradeEvent::Terminal { response, .. } => {
                     return Ok(response);
                 }
             }
-            if request.max_attempts.is_some() && attempts >= request.max_attempts.unwrap() {
-                return Err(eyre::eyre!("Max attempts reached"));
+        }
+        Err(Error::StreamEnded)
+    }
+
+    /// Resume pending trades
+    ///
+    /// This is used to follow again, after a restart, the trades that did not reach a terminal
+    /// status according to the trade store.
+    /// A trade that was created but has no recorded submission may have been sent right before
+    /// the restart, so its status is checked first: it is skipped if the service does not know
+    /// it or still reports it as `Unsigned`, since nothing is left to send it. It is also skipped,
+    /// with a warning, if its status cannot be checked.
+    /// Each stream yields a `TradeEvent::StatusChanged` for every status transition and ends with
+    /// `TradeEvent::Terminal`, or after the first error, like `send_signed_transaction_and_watch`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no trade store is configured or the trade journal cannot be read.
+    ///
+    /// # Returns
+    ///
+    /// Returns the trade id and the `TradeEventStream` of every pending trade.
+    pub async fn resume_pending_trades(&self) -> Result<Vec<(String, TradeEventStream)>> {
+        let Some(trade_store) = &self.trade_store else {
+            return Err(Error::InvalidConfig(
+                "No trade store configured".to_string(),
+            ));
+        };
+        let pending = trade_store.pending_trades().await?;
+        debug!("Resuming {} pending trades", pending.len());
+        let mut resumed = Vec::with_capacity(pending.len());
+        for trade in pending {
+            let request = CheckTradeStatusRequest::builder(&trade.trade_id).build();
+            if !trade.submitted {
+                match self.check_trade_status(request.clone()).await {
+                    Ok(response) if response.status != TradeStatus::Unsigned => {}
+                    Ok(_) => {
+                        debug!("Skipping trade {} that was never signed", trade.trade_id);
+                        continue;
+                    }
+                    Err(e) if e.is_not_found() => {
+                        debug!("Skipping trade {} that was never submitted", trade.trade_id);
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Skipping trade {} whose status cannot be checked: {}",
+                            trade.trade_id, e
+                        );
+                        continue;
+                    }
+                }
             }
-            attempts += 1;
-            tokio::time::sleep(interval_check).await;
+            let options = watch::WatchOptions {
+                poll_strategy: PollStrategy::default(),
+                max_attempts: None,
+            };
+            let events = watch::watch_trade_events(self.clone(), request, options);
+            resumed.push((trade.trade_id, events));
         }
+        Ok(resumed)
     }
 
     /// Init pool
@@ -261,11 +711,13 @@ impl Client {
     /// # Errors
     ///
     /// Returns an error if the pool cannot be initialized.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     ///     
     /// # Returns
     ///
     /// Returns the `InitPoolResponse` instance with the unsigned transaction to send to the wallet for sign & execute.
-    pub async fn init_pool(&mut self, request: InitPoolRequest) -> Result<InitPoolResponse> {
+    pub async fn init_pool(&self, request: InitPoolRequest) -> Result<InitPoolResponse> {
+        request.validate()?;
         self.service.init_pool(request).await
     }
 
@@ -276,14 +728,16 @@ impl Client {
     /// # Errors
     ///
     /// Returns an error if the liquidity cannot be added.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     ///
     /// # Returns
     ///
     /// Returns the `AddLiquidityResponse` instance with the unsigned transaction to send to the wallet for sign & execute.
     pub async fn add_liquidity(
-        &mut self,
+        &self,
         request: AddLiquidityRequest,
     ) -> Result<AddLiquidityResponse> {
+        request.validate()?;
         self.service.add_liquidity(request).await
     }
 
@@ -294,14 +748,388 @@ impl Client {
     /// # Errors
     ///
     /// Returns an error if the liquidity cannot be removed.
+    /// Returns `Error::Validation` without calling the service if the request is invalid.
     ///
     /// # Returns
     ///
     /// Returns the `RemoveLiquidityResponse` instance with the unsigned transaction to send to the wallet for sign & execute.
     pub async fn remove_liquidity(
-        &mut self,
+        &self,
         request: RemoveLiquidityRequest,
     ) -> Result<RemoveLiquidityResponse> {
+        request.validate()?;
         self.service.remove_liquidity(request).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    use crate::{
+        core::store::{InMemoryTradeStore, TradeRecord},
+        models::PriorityLevel,
+    };
+
+    #[test]
+    /// Test client can be shared between tasks
+    ///
+    /// This is used to make sure the client stays `Clone + Send + Sync`.
+    fn test_client_is_shareable() {
+        fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+        assert_shareable::<Client>();
+    }
+
+    #[tokio::test]
+    /// Test check trade statuses fallback
+    ///
+    /// This is used to test that the trades are checked one by one, in order, when the server
+    /// does not support batches, and that the batch call is not tried again.
+    async fn test_check_trade_statuses_falls_back() {
+        let (client, service) =
+            mock::MockService::with_statuses("trade", &[TradeStatus::Signed]).into_client();
+
+        let results = client
+            .check_trade_statuses(&["missing", "trade"])
+            .await
+            .unwrap();
+
+        assert!(results[0].as_ref().unwrap_err().is_not_found());
+        assert_eq!(results[1].as_ref().unwrap().status, TradeStatus::Signed);
+        assert!(client.batch_unsupported.load(Ordering::Relaxed));
+        assert_eq!(*service.status_calls.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    /// Test client side validation
+    ///
+    /// This is used to test that an invalid request is rejected before the service is called.
+    async fn test_invalid_request_is_not_sent() {
+        let (client, _) = mock::MockService::default().into_client();
+        let request = QuoteRequest {
+            token_mint_x: "So11111111111111111111111111111111111111112"
+                .parse()
+                .unwrap(),
+            token_mint_y: "So11111111111111111111111111111111111111112"
+                .parse()
+                .unwrap(),
+            amount_in: 0,
+            is_swap_x_to_y: true,
+            tracking_id: "quote".to_string(),
+        };
+
+        let error = client.get_quote(request).await.unwrap_err();
+
+        assert!(matches!(&error, Error::Validation(errors) if errors.len() == 2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test resume pending trades
+    ///
+    /// This is used to test that the pending trades of the journal are watched again and that
+    /// their transitions are recorded.
+    async fn test_resume_pending_trades() {
+        let (client, _) = mock::MockService::with_statuses(
+            "trade",
+            &[TradeStatus::Confirmed, TradeStatus::Settled],
+        )
+        .into_client();
+        let trade_store = Arc::new(InMemoryTradeStore::new());
+        let client = Client {
+            trade_store: Some(trade_store.clone()),
+            ..client
+        };
+        client
+            .send_signed_transaction(SendSignedTransactionRequest::builder("tx", "trade").build())
+            .await
+            .unwrap();
+
+        let mut resumed = client.resume_pending_trades().await.unwrap();
+        assert_eq!(resumed.len(), 1);
+        let (trade_id, events) = resumed.remove(0);
+        let events: Vec<TradeEvent> = events.map(|event| event.unwrap()).collect().await;
+
+        assert_eq!(trade_id, "trade");
+        assert!(matches!(events.last(), Some(TradeEvent::Terminal { .. })));
+        assert!(trade_store.pending_trades().await.unwrap().is_empty());
+        assert_eq!(trade_store.records().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test resume created trades
+    ///
+    /// This is used to test that a trade created without recorded submission is watched if the
+    /// service knows it as signed, and skipped if it is unknown, still unsigned, abandoned or its
+    /// status cannot be checked.
+    async fn test_resume_created_trades() {
+        let service =
+            mock::MockService::with_statuses("sent", &[TradeStatus::Signed, TradeStatus::Settled]);
+        service.script("unsigned", &[TradeStatus::Unsigned]);
+        service.script("abandoned", &[TradeStatus::Unsigned]);
+        service.fail("unavailable", Code::Unavailable);
+        let (client, _) = service.into_client();
+        let trade_store = Arc::new(InMemoryTradeStore::new());
+        let client = Client {
+            trade_store: Some(trade_store.clone()),
+            ..client
+        };
+        for trade_id in ["lost", "unsigned", "abandoned", "unavailable", "sent"] {
+            trade_store
+                .append(TradeRecord::Created {
+                    trade_id: trade_id.to_string(),
+                    order_id: "order".to_string(),
+                    unsigned_transaction: "tx".to_string(),
+                    at: SystemTime::now(),
+                })
+                .await
+                .unwrap();
+        }
+        trade_store
+            .append(TradeRecord::abandoned("abandoned"))
+            .await
+            .unwrap();
+
+        let mut resumed = client.resume_pending_trades().await.unwrap();
+        assert_eq!(resumed.len(), 1);
+        let (trade_id, events) = resumed.remove(0);
+        let events: Vec<TradeEvent> = events.map(|event| event.unwrap()).collect().await;
+
+        assert_eq!(trade_id, "sent");
+        assert!(matches!(events.last(), Some(TradeEvent::Terminal { .. })));
+    }
+
+    /// A signer appending a suffix to the transaction.
+    struct SuffixSigner;
+
+    #[async_trait::async_trait]
+    impl TransactionSigner for SuffixSigner {
+        async fn sign(&self, unsigned_transaction: &str) -> Result<String> {
+            Ok(format!("{}:signed", unsigned_transaction))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test swap
+    ///
+    /// This is used to test that the whole pipeline runs with the signer and returns every
+    /// artifact of the swap.
+    async fn test_swap() {
+        let (client, _) =
+            mock::MockService::with_statuses("trade", &[TradeStatus::Signed, TradeStatus::Settled])
+                .into_client();
+        let quote_request = QuoteRequest::builder(
+            "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX"
+                .parse()
+                .unwrap(),
+            "So11111111111111111111111111111111111111112"
+                .parse()
+                .unwrap(),
+            1_000,
+        )
+        .build()
+        .unwrap();
+        let params = SwapParams::builder(
+            quote_request,
+            "4bRZeVcTPFTFwcL8hgbEX3gdzQ37v2vr2GSmvR7X4Asp"
+                .parse()
+                .unwrap(),
+            100,
+        )
+        .tracking_id("swap")
+        .priority_level(PriorityLevel::High)
+        .compute_unit_limit(200_000)
+        .skip_verification()
+        .build();
+
+        let result = client.swap(params, &SuffixSigner).await.unwrap();
+
+        assert_eq!(result.request.min_out, 990);
+        assert_eq!(result.request.tracking_id, "swap");
+        assert_eq!(result.signed_transaction, "unsigned:990:signed");
+        assert_eq!(result.unsigned.priority_level, Some(PriorityLevel::High));
+        assert_eq!(result.unsigned.compute_unit_limit, Some(200_000));
+        assert!(result.submitted.success);
+        assert_eq!(result.transitions.len(), 2);
+        assert_eq!(result.terminal.status, TradeStatus::Settled);
+    }
+
+    #[tokio::test]
+    /// Test swap without verification policy
+    ///
+    /// This is used to test that a swap without verification policy is refused before anything
+    /// is signed, unless the verification is explicitly skipped.
+    async fn test_swap_requires_verification() {
+        let (client, service) =
+            mock::MockService::with_statuses("trade", &[TradeStatus::Settled]).into_client();
+        let quote_request = QuoteRequest::builder(
+            "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX"
+                .parse()
+                .unwrap(),
+            "So11111111111111111111111111111111111111112"
+                .parse()
+                .unwrap(),
+            1_000,
+        )
+        .build()
+        .unwrap();
+        let params = SwapParams::builder(
+            quote_request,
+            "4bRZeVcTPFTFwcL8hgbEX3gdzQ37v2vr2GSmvR7X4Asp"
+                .parse()
+                .unwrap(),
+            100,
+        )
+        .build();
+
+        let error = client.swap(params, &SuffixSigner).await.unwrap_err();
+
+        assert!(matches!(error, Error::Verification(_)));
+        assert_eq!(*service.quote_calls.lock().unwrap(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test swap with expired blockhashes
+    ///
+    /// This is used to test that the trade is re-quoted and re-created while its blockhash is
+    /// expired, that the expired trades are journaled as abandoned and that the swap fails once
+    /// the refreshes are exhausted.
+    async fn test_swap_refreshes_expired_blockhash() {
+        let (client, service) =
+            mock::MockService::with_statuses("trade", &[TradeStatus::Settled]).into_client();
+        let trade_store = Arc::new(InMemoryTradeStore::new());
+        let client = Client {
+            trade_store: Some(trade_store.clone()),
+            ..client
+        };
+        let params = || {
+            SwapParams::builder(
+                QuoteRequest::builder(
+                    "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX"
+                        .parse()
+                        .unwrap(),
+                    "So11111111111111111111111111111111111111112"
+                        .parse()
+                        .unwrap(),
+                    1_000,
+                )
+                .build()
+                .unwrap(),
+                "4bRZeVcTPFTFwcL8hgbEX3gdzQ37v2vr2GSmvR7X4Asp"
+                    .parse()
+                    .unwrap(),
+                100,
+            )
+            .skip_verification()
+            .build()
+        };
+
+        *service.expired_blockhashes.lock().unwrap() = 1;
+        let result = client.swap(params(), &SuffixSigner).await.unwrap();
+        assert!(!result.unsigned.is_blockhash_expired());
+        assert_eq!(result.terminal.status, TradeStatus::Settled);
+        assert_eq!(*service.quote_calls.lock().unwrap(), 2);
+
+        *service.quote_calls.lock().unwrap() = 0;
+        *service.expired_blockhashes.lock().unwrap() = 4;
+        let error = client.swap(params(), &SuffixSigner).await.unwrap_err();
+        assert!(matches!(error, Error::BlockhashExpired { refreshes: 3 }));
+        assert_eq!(*service.quote_calls.lock().unwrap(), 4);
+        assert!(trade_store.pending_trades().await.unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test swap with failed sends
+    ///
+    /// This is used to test that the trade is re-quoted and re-created when the service reports an
+    /// expired blockhash on send, and that any other send failure ends the swap with its logs.
+    async fn test_swap_handles_failed_sends() {
+        let (client, service) =
+            mock::MockService::with_statuses("trade", &[TradeStatus::Settled]).into_client();
+        let params = || {
+            SwapParams::builder(
+                QuoteRequest::builder(
+                    "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX"
+                        .parse()
+                        .unwrap(),
+                    "So11111111111111111111111111111111111111112"
+                        .parse()
+                        .unwrap(),
+                    1_000,
+                )
+                .build()
+                .unwrap(),
+                "4bRZeVcTPFTFwcL8hgbEX3gdzQ37v2vr2GSmvR7X4Asp"
+                    .parse()
+                    .unwrap(),
+                100,
+            )
+            .skip_verification()
+            .build()
+        };
+
+        service.fail_send(&["Transaction simulation failed: Blockhash not found"]);
+        let result = client.swap(params(), &SuffixSigner).await.unwrap();
+        assert!(result.submitted.success);
+        assert_eq!(result.terminal.status, TradeStatus::Settled);
+        assert_eq!(*service.quote_calls.lock().unwrap(), 2);
+
+        *service.status_calls.lock().unwrap() = 0;
+        service.fail_send(&["insufficient funds"]);
+        let error = client.swap(params(), &SuffixSigner).await.unwrap_err();
+        let Error::SendFailed { error_logs, .. } = error else {
+            panic!("the swap should fail to send");
+        };
+        assert_eq!(error_logs, vec!["insufficient funds".to_string()]);
+        assert_eq!(*service.status_calls.lock().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    /// Test blockhash expiry
+    ///
+    /// This is used to test that an expired blockhash is detected in the error logs and that
+    /// the trade is re-created with the same parameters.
+    async fn test_refresh_unsigned_transaction() {
+        let (client, _) = mock::MockService::with_statuses("trade", &[]).into_client();
+        let request = CreateUnsignedTransactionRequest::builder(
+            "4bRZeVcTPFTFwcL8hgbEX3gdzQ37v2vr2GSmvR7X4Asp"
+                .parse()
+                .unwrap(),
+            "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX"
+                .parse()
+                .unwrap(),
+            "So11111111111111111111111111111111111111112"
+                .parse()
+                .unwrap(),
+            1_000,
+            900,
+        )
+        .build()
+        .unwrap();
+        let mut unsigned = client
+            .create_unsigned_transaction(request.clone())
+            .await
+            .unwrap();
+        unsigned.received_at -= crate::models::BLOCKHASH_VALIDITY;
+        assert!(unsigned.is_blockhash_expired());
+
+        let failed = SendSignedTransactionResponse {
+            success: false,
+            trade_id: unsigned.trade_id.clone(),
+            error_logs: vec!["Transaction simulation failed: Blockhash not found".to_string()],
+        };
+        assert!(failed.is_blockhash_expired());
+        let failed = SendSignedTransactionResponse {
+            error_logs: vec!["insufficient funds".to_string()],
+            ..failed
+        };
+        assert!(!failed.is_blockhash_expired());
+
+        let refreshed = client
+            .refresh_unsigned_transaction(&request, &unsigned)
+            .await
+            .unwrap();
+        assert!(!refreshed.is_blockhash_expired());
+        assert_eq!(refreshed.unsigned_transaction, "unsigned:900");
+    }
+}
@@ -4,21 +4,27 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
-use std::fmt;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::future::Future;
 use std::time::Duration;
 use tonic::{
-    Request, async_trait,
+    Request, Response, Streaming, async_trait,
+    metadata::MetadataValue,
+    service::interceptor::InterceptedService,
     transport::{Channel, ClientTlsConfig},
 };
 use tracing::*;
 
-use eyre::Result;
-
 use crate::{
-    client::service::Service,
+    client::{TradeStatusStream, service::Service},
+    core::{auth::AuthInterceptor, config::Config, retry::RetryPolicy},
+    error::{Error, Result},
     integrations_pb::{
         AddLiquidityRequest as ProtoAddLiquidityRequest,
         AddLiquidityResponse as ProtoAddLiquidityResponse,
+        CheckTradeStatusBatchRequest as ProtoCheckTradeStatusBatchRequest,
+        CheckTradeStatusBatchResponse as ProtoCheckTradeStatusBatchResponse,
         CheckTradeStatusRequest as ProtoCheckTradeStatusRequest,
         CheckTradeStatusResponse as ProtoCheckTradeStatusResponse,
         CreateUnsignedTransactionRequest as ProtoCreateUnsignedTransactionRequest,
@@ -32,54 +38,47 @@ use crate::{
         SendSignedTransactionRequest as ProtoSendSignedTransactionRequest,
         SendSignedTransactionResponse as ProtoSendSignedTransactionResponse,
         darklake_integrations_service_client::DarklakeIntegrationsServiceClient,
+        trade_status_result::Outcome as ProtoTradeStatusOutcome,
     },
     models::{
-        AddLiquidityRequest, AddLiquidityResponse, CheckTradeStatusRequest,
-        CheckTradeStatusResponse, CreateUnsignedTransactionRequest,
+        AddLiquidityRequest, AddLiquidityResponse, CheckTradeStatusBatchRequest,
+        CheckTradeStatusRequest, CheckTradeStatusResponse, CreateUnsignedTransactionRequest,
         CreateUnsignedTransactionResponse, GetTradesListByUserRequest, GetTradesListByUserResponse,
         InitPoolRequest, InitPoolResponse, QuoteRequest, QuoteResponse, RemoveLiquidityRequest,
         RemoveLiquidityResponse, SendSignedTransactionRequest, SendSignedTransactionResponse,
     },
 };
 
-/// Error type for the gRPC client.
-///
-/// This enum represents the different errors that can occur when interacting with the gRPC client.
-#[derive(Debug)]
-pub(crate) enum GrpcClientError {
-    InvalidUri(String),
-    ConnectionFailed(String),
-    GrpcError(tonic::Status),
-}
+/// The generated gRPC stub, with the credentials attached to every call.
+type ServiceClient =
+    DarklakeIntegrationsServiceClient<InterceptedService<Channel, AuthInterceptor>>;
 
-impl std::error::Error for GrpcClientError {}
-
-/// Display implementation for the gRPC client error.
-///
-/// This implementation provides a human-readable representation of the gRPC client error.
-impl fmt::Display for GrpcClientError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            GrpcClientError::InvalidUri(msg) => write!(f, "Invalid URI: {}", msg),
-            GrpcClientError::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
-            GrpcClientError::GrpcError(status) => write!(f, "gRPC error: {}", status),
-        }
-    }
-}
+/// The metadata entry carrying the tracking id of the calls whose message has no tracking id
+/// field.
+const TRACKING_ID_HEADER: &str = "x-tracking-id";
 
-impl From<tonic::Status> for GrpcClientError {
-    fn from(status: tonic::Status) -> Self {
-        GrpcClientError::GrpcError(status)
+/// Wraps the message in a request carrying the tracking id in its metadata. A tracking id that
+/// is not a valid metadata value is not sent.
+fn tracked_request<T>(message: T, tracking_id: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    if let Ok(value) = MetadataValue::try_from(tracking_id) {
+        request.metadata_mut().insert(TRACKING_ID_HEADER, value);
     }
+    request
 }
 
 /// gRPC client for interacting with the Darklake Integrations service
+///
+/// Cloning is cheap: the underlying tonic `Channel` is shared and multiplexes
+/// concurrent requests, so every call works on its own clone of the stub.
+#[derive(Clone)]
 pub(crate) struct DarklakeIntegrationsClient {
-    client: DarklakeIntegrationsServiceClient<Channel>,
+    client: ServiceClient,
+    retry_policy: RetryPolicy,
 }
 
 impl DarklakeIntegrationsClient {
-    /// Create a new client with the given server address
+    /// Create a new client from the given configuration
     ///
     /// # Errors
     ///
@@ -88,10 +87,12 @@ impl DarklakeIntegrationsClient {
     /// # Returns
     ///
     /// Returns the `DarklakeIntegrationsClient` instance.
-    pub(crate) async fn new(server_addr: String) -> Result<Self, GrpcClientError> {
-        let uri = server_addr
+    pub(crate) async fn new(config: &Config) -> Result<Self> {
+        let uri = config
+            .url
+            .as_str()
             .parse::<tonic::transport::Uri>()
-            .map_err(|e| GrpcClientError::InvalidUri(e.to_string()))?;
+            .map_err(|e| Error::InvalidUri(e.to_string()))?;
 
         let mut endpoint = Channel::builder(uri.clone()).timeout(Duration::from_secs(30));
 
@@ -103,17 +104,63 @@ impl DarklakeIntegrationsClient {
 
             endpoint = endpoint
                 .tls_config(tls)
-                .map_err(|e| GrpcClientError::ConnectionFailed(e.to_string()))?;
+                .map_err(|e| Error::ConnectionFailed(e.to_string()))?;
         }
 
         let channel = endpoint
             .connect()
             .await
-            .map_err(|e| GrpcClientError::ConnectionFailed(e.to_string()))?;
+            .map_err(|e| Error::ConnectionFailed(e.to_string()))?;
 
-        let client = DarklakeIntegrationsServiceClient::new(channel.clone());
+        let client = DarklakeIntegrationsServiceClient::with_interceptor(
+            channel,
+            AuthInterceptor::new(config.credentials.clone()),
+        );
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            retry_policy: config.retry_policy.clone(),
+        })
+    }
+
+    /// Call with retry
+    ///
+    /// This is used to run a call against the service, retrying it according to the retry policy
+    /// when `retry` is set and the failure is transient. A client side timeout is retried as
+    /// `DeadlineExceeded`. Every attempt works on its own clone of the stub, so the request must
+    /// be rebuilt by `call` on each attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error if the call does not succeed within the allowed attempts.
+    async fn call_with_retry<T, F, Fut>(&self, method: &str, retry: bool, mut call: F) -> Result<T>
+    where
+        F: FnMut(ServiceClient) -> Fut,
+        Fut: Future<Output = Result<Response<T>, tonic::Status>>,
+    {
+        let mut retries = 0;
+        loop {
+            let error = match call(self.client.clone()).await {
+                Ok(response) => return Ok(response.into_inner()),
+                Err(status) => Error::from(status),
+            };
+            match error.code() {
+                Some(code)
+                    if retry
+                        && retries < self.retry_policy.max_retries
+                        && self.retry_policy.is_retryable(code) =>
+                {
+                    let backoff = self.retry_policy.backoff(retries);
+                    retries += 1;
+                    warn!(
+                        "{} failed with {:?}, retry {}/{} in {:?}",
+                        method, code, retries, self.retry_policy.max_retries, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+                _ => return Err(error),
+            }
+        }
     }
 
     /// Get quote
@@ -128,13 +175,16 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoQuoteResponse` instance.
     async fn get_quote(
-        &mut self,
+        &self,
         request: ProtoQuoteRequest,
-    ) -> Result<ProtoQuoteResponse, GrpcClientError> {
+        tracking_id: &str,
+    ) -> Result<ProtoQuoteResponse> {
         debug!("Getting quote for request: {:?}", request);
-        let response = self.client.quote(Request::new(request)).await?;
-
-        Ok(response.into_inner())
+        self.call_with_retry("quote", true, |mut client| {
+            let request = request.clone();
+            async move { client.quote(tracked_request(request, tracking_id)).await }
+        })
+        .await
     }
 
     /// Create unsigned transaction
@@ -149,15 +199,19 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoCreateUnsignedTransactionResponse` instance.
     async fn create_unsigned_transaction(
-        &mut self,
+        &self,
         request: ProtoCreateUnsignedTransactionRequest,
-    ) -> Result<ProtoCreateUnsignedTransactionResponse, GrpcClientError> {
+    ) -> Result<ProtoCreateUnsignedTransactionResponse> {
         debug!("Creating unsigned transaction for request: {:?}", request);
-        let response = self
-            .client
-            .create_unsigned_transaction(Request::new(request))
-            .await?;
-        Ok(response.into_inner())
+        self.call_with_retry("create_unsigned_transaction", false, |mut client| {
+            let request = request.clone();
+            async move {
+                client
+                    .create_unsigned_transaction(Request::new(request))
+                    .await
+            }
+        })
+        .await
     }
 
     /// Send signed transaction
@@ -172,15 +226,19 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoSendSignedTransactionResponse` instance.
     async fn send_signed_transaction(
-        &mut self,
+        &self,
         request: ProtoSendSignedTransactionRequest,
-    ) -> Result<ProtoSendSignedTransactionResponse, GrpcClientError> {
+    ) -> Result<ProtoSendSignedTransactionResponse> {
         debug!("Sending signed transaction for request: {:?}", request);
-        let response = self
-            .client
-            .send_signed_transaction(Request::new(request))
-            .await?;
-        Ok(response.into_inner())
+        self.call_with_retry(
+            "send_signed_transaction",
+            self.retry_policy.retry_send_signed_transaction,
+            |mut client| {
+                let request = request.clone();
+                async move { client.send_signed_transaction(Request::new(request)).await }
+            },
+        )
+        .await
     }
 
     /// Check trade status
@@ -195,15 +253,63 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoCheckTradeStatusResponse` instance.
     async fn check_trade_status(
-        &mut self,
+        &self,
         request: ProtoCheckTradeStatusRequest,
-    ) -> Result<ProtoCheckTradeStatusResponse, GrpcClientError> {
+    ) -> Result<ProtoCheckTradeStatusResponse> {
         debug!("Checking trade status for request: {:?}", request);
-        let response = self
-            .client
-            .check_trade_status(Request::new(request))
-            .await?;
-        Ok(response.into_inner())
+        self.call_with_retry("check_trade_status", true, |mut client| {
+            let request = request.clone();
+            async move { client.check_trade_status(Request::new(request)).await }
+        })
+        .await
+    }
+
+    /// Watch trade status
+    ///
+    /// This is used to open a stream of status updates for a trade.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream cannot be opened, e.g. with an `Unimplemented` status if the
+    /// server does not support streaming.
+    ///
+    /// # Returns
+    ///
+    /// Returns the stream of `ProtoCheckTradeStatusResponse` instances.
+    async fn watch_trade_status(
+        &self,
+        request: ProtoCheckTradeStatusRequest,
+    ) -> Result<Streaming<ProtoCheckTradeStatusResponse>> {
+        debug!("Watching trade status for request: {:?}", request);
+        self.call_with_retry("watch_trade_status", true, |mut client| {
+            let request = request.clone();
+            async move { client.watch_trade_status(Request::new(request)).await }
+        })
+        .await
+    }
+
+    /// Check trade status batch
+    ///
+    /// This is used to check the status of several trades in one call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call fails, e.g. with an `Unimplemented` status if the server does
+    /// not support batches.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `ProtoCheckTradeStatusBatchResponse` instance.
+    async fn check_trade_status_batch(
+        &self,
+        request: ProtoCheckTradeStatusBatchRequest,
+    ) -> Result<ProtoCheckTradeStatusBatchResponse> {
+        debug!("Checking trade status batch for request: {:?}", request);
+        self.call_with_retry("check_trade_status_batch", true, |mut client| {
+            let request = request.clone();
+            async move { client.check_trade_status_batch(Request::new(request)).await }
+        })
+        .await
     }
 
     /// Get trades list by user
@@ -218,15 +324,20 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoGetTradesListByUserResponse` instance.
     async fn get_trades_list_by_user(
-        &mut self,
+        &self,
         request: ProtoGetTradesListByUserRequest,
-    ) -> Result<ProtoGetTradesListByUserResponse, GrpcClientError> {
+        tracking_id: &str,
+    ) -> Result<ProtoGetTradesListByUserResponse> {
         debug!("Getting trades list by user for request: {:?}", request);
-        let response = self
-            .client
-            .get_trades_list_by_user(Request::new(request))
-            .await?;
-        Ok(response.into_inner())
+        self.call_with_retry("get_trades_list_by_user", true, |mut client| {
+            let request = request.clone();
+            async move {
+                client
+                    .get_trades_list_by_user(tracked_request(request, tracking_id))
+                    .await
+            }
+        })
+        .await
     }
 
     /// Init pool
@@ -241,12 +352,20 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoInitPoolResponse` instance with the unsigned transaction to send to the wallet for sign & execute.
     async fn init_pool(
-        &mut self,
+        &self,
         request: ProtoInitPoolRequest,
-    ) -> Result<ProtoInitPoolResponse, GrpcClientError> {
+        tracking_id: &str,
+    ) -> Result<ProtoInitPoolResponse> {
         debug!("Init pool for request: {:?}", request);
-        let response = self.client.init_pool(Request::new(request)).await?;
-        Ok(response.into_inner())
+        self.call_with_retry("init_pool", false, |mut client| {
+            let request = request.clone();
+            async move {
+                client
+                    .init_pool(tracked_request(request, tracking_id))
+                    .await
+            }
+        })
+        .await
     }
 
     /// Add liquidity
@@ -261,12 +380,20 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoAddLiquidityResponse` instance with the unsigned transaction to send to the wallet for sign & execute.
     async fn add_liquidity(
-        &mut self,
+        &self,
         request: ProtoAddLiquidityRequest,
-    ) -> Result<ProtoAddLiquidityResponse, GrpcClientError> {
+        tracking_id: &str,
+    ) -> Result<ProtoAddLiquidityResponse> {
         info!("Adding liquidity for request: {:?}", request);
-        let response = self.client.add_liquidity(Request::new(request)).await?;
-        Ok(response.into_inner())
+        self.call_with_retry("add_liquidity", false, |mut client| {
+            let request = request.clone();
+            async move {
+                client
+                    .add_liquidity(tracked_request(request, tracking_id))
+                    .await
+            }
+        })
+        .await
     }
 
     /// Remove liquidity
@@ -281,12 +408,20 @@ impl DarklakeIntegrationsClient {
     ///
     /// Returns the `ProtoRemoveLiquidityResponse` instance with the unsigned transaction to send to the wallet for sign & execute.
     async fn remove_liquidity(
-        &mut self,
+        &self,
         request: ProtoRemoveLiquidityRequest,
-    ) -> Result<ProtoRemoveLiquidityResponse, GrpcClientError> {
+        tracking_id: &str,
+    ) -> Result<ProtoRemoveLiquidityResponse> {
         debug!("Removing liquidity for request: {:?}", request);
-        let response = self.client.remove_liquidity(Request::new(request)).await?;
-        Ok(response.into_inner())
+        self.call_with_retry("remove_liquidity", false, |mut client| {
+            let request = request.clone();
+            async move {
+                client
+                    .remove_liquidity(tracked_request(request, tracking_id))
+                    .await
+            }
+        })
+        .await
     }
 }
 
@@ -303,9 +438,13 @@ impl Service for DarklakeIntegrationsClient {
     /// # Returns
     ///
     /// Returns the `QuoteResponse` instance.
-    async fn quote(&mut self, request: QuoteRequest) -> Result<QuoteResponse> {
-        let proto_response = self.get_quote(request.into()).await?;
-        Ok(proto_response.into())
+    async fn quote(&self, request: QuoteRequest) -> Result<QuoteResponse> {
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .get_quote(request.into(), &tracking_id)
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
+        proto_response.try_into()
     }
 
     /// Create unsigned transaction
@@ -320,10 +459,14 @@ impl Service for DarklakeIntegrationsClient {
     ///
     /// Returns the `CreateUnsignedTransactionResponse` instance.
     async fn create_unsigned_transaction(
-        &mut self,
+        &self,
         request: CreateUnsignedTransactionRequest,
     ) -> Result<CreateUnsignedTransactionResponse> {
-        let proto_response = self.create_unsigned_transaction(request.into()).await?;
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .create_unsigned_transaction(request.into())
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
         Ok(proto_response.into())
     }
 
@@ -339,10 +482,14 @@ impl Service for DarklakeIntegrationsClient {
     ///
     /// Returns the `SendSignedTransactionResponse` instance.
     async fn send_signed_transaction(
-        &mut self,
+        &self,
         request: SendSignedTransactionRequest,
     ) -> Result<SendSignedTransactionResponse> {
-        let proto_response = self.send_signed_transaction(request.into()).await?;
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .send_signed_transaction(request.into())
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
         Ok(proto_response.into())
     }
 
@@ -358,13 +505,72 @@ impl Service for DarklakeIntegrationsClient {
     ///
     /// Returns the `CheckTradeStatusResponse` instance.
     async fn check_trade_status(
-        &mut self,
+        &self,
         request: CheckTradeStatusRequest,
     ) -> Result<CheckTradeStatusResponse> {
-        let proto_response = self.check_trade_status(request.into()).await?;
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .check_trade_status(request.into())
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
         Ok(proto_response.into())
     }
 
+    /// Watch trade status
+    ///
+    /// This is used to open a stream of status updates for a trade.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream cannot be opened.
+    ///
+    /// # Returns
+    ///
+    /// Returns the stream of `CheckTradeStatusResponse` instances.
+    async fn watch_trade_status(
+        &self,
+        request: CheckTradeStatusRequest,
+    ) -> Result<TradeStatusStream> {
+        let tracking_id = request.tracking_id.clone();
+        let stream = self
+            .watch_trade_status(request.into())
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
+        Ok(stream
+            .map(move |item| match item {
+                Ok(response) => Ok(response.into()),
+                Err(status) => Err(Error::from(status).with_tracking_id(&tracking_id)),
+            })
+            .boxed())
+    }
+
+    /// Check trade status batch
+    ///
+    /// This is used to check the status of several trades in one call.
+    /// The results are returned in the order of the trade ids of the request, once per
+    /// occurrence of a repeated id. A trade missing from the response is reported with a
+    /// `NotFound` error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call fails as a whole.
+    ///
+    /// # Returns
+    ///
+    /// Returns one `CheckTradeStatusResponse` or error per trade id.
+    async fn check_trade_status_batch(
+        &self,
+        request: CheckTradeStatusBatchRequest,
+    ) -> Result<Vec<Result<CheckTradeStatusResponse>>> {
+        let tracking_id = request.tracking_id.clone();
+        let trade_ids = request.trade_ids.clone();
+        let proto_response = self
+            .check_trade_status_batch(request.into())
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
+        Ok(batch_results(trade_ids, proto_response, &tracking_id))
+    }
+
     /// Get trades list by user
     ///
     /// This is used to get the trades list by user.
@@ -377,11 +583,15 @@ impl Service for DarklakeIntegrationsClient {
     ///
     /// Returns the `GetTradesListByUserResponse` instance.
     async fn get_trades_list_by_user(
-        &mut self,
+        &self,
         request: GetTradesListByUserRequest,
     ) -> Result<GetTradesListByUserResponse> {
-        let proto_response = self.get_trades_list_by_user(request.into()).await?;
-        Ok(proto_response.into())
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .get_trades_list_by_user(request.into(), &tracking_id)
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
+        proto_response.try_into()
     }
 
     /// Init pool
@@ -395,8 +605,12 @@ impl Service for DarklakeIntegrationsClient {
     /// # Returns
     ///
     /// Returns the `InitPoolResponse` instance.
-    async fn init_pool(&mut self, request: InitPoolRequest) -> Result<InitPoolResponse> {
-        let proto_response = self.init_pool(request.into()).await?;
+    async fn init_pool(&self, request: InitPoolRequest) -> Result<InitPoolResponse> {
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .init_pool(request.into(), &tracking_id)
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
         Ok(proto_response.into())
     }
 
@@ -411,11 +625,12 @@ impl Service for DarklakeIntegrationsClient {
     /// # Returns
     ///
     /// Returns the `AddLiquidityResponse` instance.
-    async fn add_liquidity(
-        &mut self,
-        request: AddLiquidityRequest,
-    ) -> Result<AddLiquidityResponse> {
-        let proto_response = self.add_liquidity(request.into()).await?;
+    async fn add_liquidity(&self, request: AddLiquidityRequest) -> Result<AddLiquidityResponse> {
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .add_liquidity(request.into(), &tracking_id)
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
         Ok(proto_response.into())
     }
 
@@ -431,14 +646,51 @@ impl Service for DarklakeIntegrationsClient {
     ///
     /// Returns the `RemoveLiquidityResponse` instance.
     async fn remove_liquidity(
-        &mut self,
+        &self,
         request: RemoveLiquidityRequest,
     ) -> Result<RemoveLiquidityResponse> {
-        let proto_response = self.remove_liquidity(request.into()).await?;
+        let tracking_id = request.tracking_id.clone();
+        let proto_response = self
+            .remove_liquidity(request.into(), &tracking_id)
+            .await
+            .map_err(|e| e.with_tracking_id(&tracking_id))?;
         Ok(proto_response.into())
     }
 }
 
+/// Matches the results of a batch response with the trade ids of the request.
+fn batch_results(
+    trade_ids: Vec<String>,
+    response: ProtoCheckTradeStatusBatchResponse,
+    tracking_id: &str,
+) -> Vec<Result<CheckTradeStatusResponse>> {
+    let outcomes: HashMap<String, Option<ProtoTradeStatusOutcome>> = response
+        .results
+        .into_iter()
+        .map(|result| (result.trade_id, result.outcome))
+        .collect();
+    trade_ids
+        .into_iter()
+        .map(
+            |trade_id| match outcomes.get(&trade_id).cloned().flatten() {
+                Some(ProtoTradeStatusOutcome::Status(status)) => Ok(CheckTradeStatusResponse {
+                    trade_id,
+                    status: status.into(),
+                }),
+                Some(ProtoTradeStatusOutcome::Error(error)) => Err(Error::from(
+                    tonic::Status::new(error.code.into(), error.message),
+                )
+                .with_tracking_id(tracking_id)),
+                None => Err(Error::from(tonic::Status::not_found(format!(
+                    "No status returned for trade {}",
+                    trade_id
+                )))
+                .with_tracking_id(tracking_id)),
+            },
+        )
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -450,8 +702,79 @@ mod tests {
     async fn test_client_creation() {
         // This test would require a running server
         // For now, we'll just test that the client can be created with a valid address
-        let result = DarklakeIntegrationsClient::new("http://[::1]:50051".to_string()).await;
+        let config = Config::builder()
+            .url("http://[::1]:50051")
+            .unwrap()
+            .final_url(true)
+            .build()
+            .unwrap();
+        let result = DarklakeIntegrationsClient::new(&config).await;
         // This will likely fail without a server, but we can test the creation logic
         assert!(result.is_err() || result.is_ok());
     }
+
+    #[tokio::test(start_paused = true)]
+    /// Test timed out calls are retried
+    ///
+    /// This is used to test that a client side timeout, which tonic reports as `Cancelled`, is
+    /// retried as `DeadlineExceeded` and reported as `Error::Timeout`.
+    async fn test_call_with_retry_retries_timeouts() {
+        let channel = tonic::transport::Endpoint::from_static("http://[::1]:50051").connect_lazy();
+        let client = DarklakeIntegrationsClient {
+            client: DarklakeIntegrationsServiceClient::with_interceptor(
+                channel,
+                AuthInterceptor::new(Default::default()),
+            ),
+            retry_policy: RetryPolicy::builder().max_retries(2).build(),
+        };
+        let mut attempts = 0;
+
+        let result: Result<()> = client
+            .call_with_retry("quote", true, |_| {
+                attempts += 1;
+                async {
+                    Err(tonic::Status::from_error(Box::new(tonic::TimeoutExpired(
+                        (),
+                    ))))
+                }
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::Timeout { .. })));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    /// Test batch results
+    ///
+    /// This is used to test that a trade id repeated in the request gets the same result every
+    /// time, and that a trade missing from the response is not found.
+    fn test_batch_results() {
+        let response = ProtoCheckTradeStatusBatchResponse {
+            results: vec![crate::integrations_pb::TradeStatusResult {
+                trade_id: "trade".to_string(),
+                outcome: Some(ProtoTradeStatusOutcome::Status(
+                    crate::integrations_pb::TradeStatus::Settled.into(),
+                )),
+            }],
+        };
+        let trade_ids = vec![
+            "trade".to_string(),
+            "missing".to_string(),
+            "trade".to_string(),
+        ];
+
+        let results = batch_results(trade_ids, response, "tracking");
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[0].as_ref().unwrap().status,
+            crate::models::TradeStatus::Settled
+        );
+        assert!(results[1].as_ref().unwrap_err().is_not_found());
+        assert_eq!(
+            results[2].as_ref().unwrap().status,
+            crate::models::TradeStatus::Settled
+        );
+    }
 }
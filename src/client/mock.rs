@@ -0,0 +1,227 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use futures::{StreamExt, stream};
+use tonic::Code;
+
+use crate::{
+    client::{Client, TradeStatusStream, service::Service},
+    core::retry::RetryPolicy,
+    error::Result,
+    models::{
+        AddLiquidityRequest, AddLiquidityResponse, BLOCKHASH_VALIDITY,
+        CheckTradeStatusBatchRequest, CheckTradeStatusRequest, CheckTradeStatusResponse,
+        CreateUnsignedTransactionRequest, CreateUnsignedTransactionResponse,
+        GetTradesListByUserRequest, GetTradesListByUserResponse, InitPoolRequest, InitPoolResponse,
+        QuoteRequest, QuoteResponse, RemoveLiquidityRequest, RemoveLiquidityResponse,
+        SendSignedTransactionRequest, SendSignedTransactionResponse, TradeStatus,
+    },
+};
+
+/// Mock service
+///
+/// This service replays a scripted sequence of statuses for each trade. The last status of a
+/// script is repeated once the script is exhausted, and the checks of a failing trade fail with
+/// its code. Quotes are one to one and unsigned
+/// transactions are created for the trade `trade`. Each trade status stream replays the next
+/// scripted stream, and streaming is unimplemented when none is left. The next
+/// `expired_blockhashes` unsigned transactions are created with an expired blockhash, and the next
+/// signed transactions fail to be sent with the scripted error logs. Every other call is
+/// unimplemented.
+#[derive(Default)]
+pub(crate) struct MockService {
+    statuses: Mutex<HashMap<String, VecDeque<TradeStatus>>>,
+    streams: Mutex<VecDeque<Vec<std::result::Result<TradeStatus, Code>>>>,
+    failures: Mutex<HashMap<String, Code>>,
+    failed_sends: Mutex<VecDeque<Vec<String>>>,
+    pub(crate) status_calls: Mutex<u32>,
+    pub(crate) watch_calls: Mutex<u32>,
+    pub(crate) quote_calls: Mutex<u32>,
+    pub(crate) expired_blockhashes: Mutex<u32>,
+}
+
+impl MockService {
+    pub(crate) fn with_statuses(trade_id: &str, statuses: &[TradeStatus]) -> Self {
+        let service = Self::default();
+        service.script(trade_id, statuses);
+        service
+    }
+
+    pub(crate) fn script(&self, trade_id: &str, statuses: &[TradeStatus]) {
+        self.statuses
+            .lock()
+            .unwrap()
+            .insert(trade_id.to_string(), statuses.iter().cloned().collect());
+    }
+
+    /// Makes every status check of the trade fail with the code.
+    pub(crate) fn fail(&self, trade_id: &str, code: Code) {
+        self.failures
+            .lock()
+            .unwrap()
+            .insert(trade_id.to_string(), code);
+    }
+
+    /// Makes the next signed transaction fail to be sent with the error logs.
+    pub(crate) fn fail_send(&self, error_logs: &[&str]) {
+        self.failed_sends
+            .lock()
+            .unwrap()
+            .push_back(error_logs.iter().map(|log| log.to_string()).collect());
+    }
+
+    /// Scripts the items of the next trade status stream, an error ends the stream.
+    pub(crate) fn script_stream(&self, items: &[std::result::Result<TradeStatus, Code>]) {
+        self.streams.lock().unwrap().push_back(items.to_vec());
+    }
+
+    pub(crate) fn into_client(self) -> (Client, Arc<MockService>) {
+        let service = Arc::new(self);
+        (
+            Client {
+                service: service.clone(),
+                trade_store: None,
+                retry_policy: RetryPolicy::default(),
+                batch_unsupported: Default::default(),
+                streaming_unsupported: Default::default(),
+            },
+            service,
+        )
+    }
+}
+
+fn unimplemented<T>() -> Result<T> {
+    Err(tonic::Status::unimplemented("not implemented by the mock").into())
+}
+
+#[async_trait]
+impl Service for MockService {
+    async fn quote(&self, request: QuoteRequest) -> Result<QuoteResponse> {
+        *self.quote_calls.lock().unwrap() += 1;
+        Ok(QuoteResponse {
+            token_mint_x: request.token_mint_x,
+            token_mint_y: request.token_mint_y,
+            amount_in: request.amount_in,
+            amount_out: request.amount_in,
+            fee_amount: 0,
+            fee_pct: 0.0,
+            is_swap_x_to_y: request.is_swap_x_to_y,
+            received_at: Instant::now(),
+        })
+    }
+
+    async fn create_unsigned_transaction(
+        &self,
+        request: CreateUnsignedTransactionRequest,
+    ) -> Result<CreateUnsignedTransactionResponse> {
+        let mut received_at = Instant::now();
+        let mut expired_blockhashes = self.expired_blockhashes.lock().unwrap();
+        if *expired_blockhashes > 0 {
+            *expired_blockhashes -= 1;
+            received_at -= BLOCKHASH_VALIDITY;
+        }
+        Ok(CreateUnsignedTransactionResponse {
+            unsigned_transaction: format!("unsigned:{}", request.min_out),
+            order_id: "order".to_string(),
+            trade_id: "trade".to_string(),
+            received_at,
+            priority_fee_micro_lamports: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+            priority_level: request.priority_level,
+        })
+    }
+
+    async fn send_signed_transaction(
+        &self,
+        request: SendSignedTransactionRequest,
+    ) -> Result<SendSignedTransactionResponse> {
+        let error_logs = self.failed_sends.lock().unwrap().pop_front();
+        Ok(SendSignedTransactionResponse {
+            success: error_logs.is_none(),
+            trade_id: request.trade_id,
+            error_logs: error_logs.unwrap_or_default(),
+        })
+    }
+
+    async fn check_trade_status(
+        &self,
+        request: CheckTradeStatusRequest,
+    ) -> Result<CheckTradeStatusResponse> {
+        *self.status_calls.lock().unwrap() += 1;
+        if let Some(code) = self.failures.lock().unwrap().get(&request.trade_id) {
+            return Err(tonic::Status::new(*code, "scripted failure").into());
+        }
+        let mut statuses = self.statuses.lock().unwrap();
+        let Some(script) = statuses.get_mut(&request.trade_id) else {
+            return Err(tonic::Status::not_found("trade not found").into());
+        };
+        let status = if script.len() > 1 {
+            script.pop_front().unwrap()
+        } else {
+            script.front().cloned().unwrap()
+        };
+        Ok(CheckTradeStatusResponse {
+            trade_id: request.trade_id,
+            status,
+        })
+    }
+
+    async fn watch_trade_status(
+        &self,
+        request: CheckTradeStatusRequest,
+    ) -> Result<TradeStatusStream> {
+        *self.watch_calls.lock().unwrap() += 1;
+        let Some(items) = self.streams.lock().unwrap().pop_front() else {
+            return unimplemented();
+        };
+        let trade_id = request.trade_id;
+        Ok(stream::iter(items)
+            .map(move |item| match item {
+                Ok(status) => Ok(CheckTradeStatusResponse {
+                    trade_id: trade_id.clone(),
+                    status,
+                }),
+                Err(code) => Err(tonic::Status::new(code, "scripted failure").into()),
+            })
+            .boxed())
+    }
+
+    async fn check_trade_status_batch(
+        &self,
+        _request: CheckTradeStatusBatchRequest,
+    ) -> Result<Vec<Result<CheckTradeStatusResponse>>> {
+        unimplemented()
+    }
+
+    async fn get_trades_list_by_user(
+        &self,
+        _request: GetTradesListByUserRequest,
+    ) -> Result<GetTradesListByUserResponse> {
+        unimplemented()
+    }
+
+    async fn init_pool(&self, _request: InitPoolRequest) -> Result<InitPoolResponse> {
+        unimplemented()
+    }
+
+    async fn add_liquidity(&self, _request: AddLiquidityRequest) -> Result<AddLiquidityResponse> {
+        unimplemented()
+    }
+
+    async fn remove_liquidity(
+        &self,
+        _request: RemoveLiquidityRequest,
+    ) -> Result<RemoveLiquidityResponse> {
+        unimplemented()
+    }
+}
@@ -5,43 +5,54 @@
 // https://opensource.org/licenses/MIT.
 
 use async_trait::async_trait;
-use eyre::Result;
 
+use crate::client::TradeStatusStream;
+use crate::error::Result;
 use crate::models::{
-    AddLiquidityRequest, AddLiquidityResponse, CheckTradeStatusRequest, CheckTradeStatusResponse,
-    CreateUnsignedTransactionRequest, CreateUnsignedTransactionResponse,
-    GetTradesListByUserRequest, GetTradesListByUserResponse, InitPoolRequest, InitPoolResponse,
-    QuoteRequest, QuoteResponse, RemoveLiquidityRequest, RemoveLiquidityResponse,
-    SendSignedTransactionRequest, SendSignedTransactionResponse,
+    AddLiquidityRequest, AddLiquidityResponse, CheckTradeStatusBatchRequest,
+    CheckTradeStatusRequest, CheckTradeStatusResponse, CreateUnsignedTransactionRequest,
+    CreateUnsignedTransactionResponse, GetTradesListByUserRequest, GetTradesListByUserResponse,
+    InitPoolRequest, InitPoolResponse, QuoteRequest, QuoteResponse, RemoveLiquidityRequest,
+    RemoveLiquidityResponse, SendSignedTransactionRequest, SendSignedTransactionResponse,
 };
 
 #[async_trait]
 /// Service trait
 ///
 /// This trait is used to define the methods that must be implemented by the service.
-pub(crate) trait Service {
-    async fn quote(&mut self, request: QuoteRequest) -> Result<QuoteResponse>;
+/// Implementations must be safe to share between tasks, as a single `Client` and its clones
+/// dispatch every call through the same service instance.
+pub(crate) trait Service: Send + Sync {
+    async fn quote(&self, request: QuoteRequest) -> Result<QuoteResponse>;
     async fn create_unsigned_transaction(
-        &mut self,
+        &self,
         request: CreateUnsignedTransactionRequest,
     ) -> Result<CreateUnsignedTransactionResponse>;
     async fn send_signed_transaction(
-        &mut self,
+        &self,
         request: SendSignedTransactionRequest,
     ) -> Result<SendSignedTransactionResponse>;
     async fn check_trade_status(
-        &mut self,
+        &self,
         request: CheckTradeStatusRequest,
     ) -> Result<CheckTradeStatusResponse>;
+    async fn watch_trade_status(
+        &self,
+        request: CheckTradeStatusRequest,
+    ) -> Result<TradeStatusStream>;
+    /// Returns one result per trade id of the request, in the same order.
+    async fn check_trade_status_batch(
+        &self,
+        request: CheckTradeStatusBatchRequest,
+    ) -> Result<Vec<Result<CheckTradeStatusResponse>>>;
     async fn get_trades_list_by_user(
-        &mut self,
+        &self,
         request: GetTradesListByUserRequest,
     ) -> Result<GetTradesListByUserResponse>;
-    async fn init_pool(&mut self, request: InitPoolRequest) -> Result<InitPoolResponse>;
-    async fn add_liquidity(&mut self, request: AddLiquidityRequest)
-    -> Result<AddLiquidityResponse>;
+    async fn init_pool(&self, request: InitPoolRequest) -> Result<InitPoolResponse>;
+    async fn add_liquidity(&self, request: AddLiquidityRequest) -> Result<AddLiquidityResponse>;
     async fn remove_liquidity(
-        &mut self,
+        &self,
         request: RemoveLiquidityRequest,
     ) -> Result<RemoveLiquidityResponse>;
 }
@@ -0,0 +1,371 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::*;
+
+use crate::{
+    client::{
+        Client,
+        watch::{self, TransitionTracker},
+    },
+    core::store::{PendingTrade, TradeRecord},
+    error::Result,
+    models::{CheckTradeStatusResponse, TradeEvent, TradeStatus},
+};
+
+/// The default interval between two rounds of checks, in milliseconds.
+const DEFAULT_INTERVAL_MILLIS: u64 = 500;
+
+/// The default maximum number of trades checked in one batch.
+const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// The default number of events kept for slow subscribers.
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// A trade followed by the tracker.
+struct TrackedTrade {
+    transitions: TransitionTracker,
+    status: Option<TradeStatus>,
+    tracked_at: SystemTime,
+}
+
+/// State shared between the tracker and its background task.
+struct Shared {
+    trades: Mutex<HashMap<String, TrackedTrade>>,
+    events: broadcast::Sender<TradeEvent>,
+}
+
+impl Shared {
+    /// Returns the ids of the trades to check.
+    fn trade_ids(&self) -> Vec<String> {
+        self.trades.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Records the result of a check and publishes the resulting events.
+    ///
+    /// Terminal trades stop being tracked.
+    ///
+    /// # Returns
+    ///
+    /// Returns the new status of the trade, if it changed.
+    fn record(
+        &self,
+        trade_id: &str,
+        response: Result<CheckTradeStatusResponse>,
+    ) -> Option<TradeStatus> {
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Failed to check the status of trade {}: {}", trade_id, e);
+                return None;
+            }
+        };
+        let events = {
+            let mut trades = self.trades.lock().unwrap();
+            // The trade may have been untracked while it was checked.
+            let trade = trades.get_mut(trade_id)?;
+            trade.status = Some(response.status.clone());
+            let terminal = response.status.is_terminal();
+            let events = watch::transition_events(&mut trade.transitions, response);
+            if terminal {
+                trades.remove(trade_id);
+            }
+            events
+        };
+        let mut changed = None;
+        for event in events.into_iter().flatten() {
+            if let TradeEvent::StatusChanged(transition) | TradeEvent::Anomaly(transition) = &event
+            {
+                changed = Some(transition.to.clone());
+            }
+            // Sending only fails when there is no subscriber, the event is dropped then.
+            let _ = self.events.send(event);
+        }
+        changed
+    }
+}
+
+/// Trade tracker
+///
+/// This struct is used to follow many trades at once. Trade ids are added at runtime with
+/// `track`, their statuses are checked in batches with `Client::check_trade_statuses` on a
+/// fixed interval, and the resulting
+/// `TradeEvent`s (`StatusChanged` then `Terminal`) are published on one broadcast channel.
+/// A trade stops being tracked once it reaches a terminal status.
+///
+/// The checks run in a background task, which stops when the tracker is dropped, when `stop` is
+/// called or when the cancellation token of the builder is cancelled.
+/// The tracker must be built within a Tokio runtime.
+pub struct TradeTracker {
+    shared: Arc<Shared>,
+    cancellation_token: CancellationToken,
+}
+
+impl TradeTracker {
+    /// Creates a new `TradeTrackerBuilder` for the given client.
+    pub fn builder(client: &Client) -> TradeTrackerBuilder {
+        TradeTrackerBuilder::new(client)
+    }
+
+    /// Starts tracking a trade.
+    ///
+    /// Its status is checked during the next round of checks.
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the trade is already tracked.
+    pub fn track(&self, trade_id: &str) -> bool {
+        let mut trades = self.shared.trades.lock().unwrap();
+        if trades.contains_key(trade_id) {
+            return false;
+        }
+        trades.insert(
+            trade_id.to_string(),
+            TrackedTrade {
+                transitions: TransitionTracker::new(trade_id),
+                status: None,
+                tracked_at: SystemTime::now(),
+            },
+        );
+        true
+    }
+
+    /// Stops tracking a trade without waiting for a terminal status.
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the trade was not tracked.
+    pub fn untrack(&self, trade_id: &str) -> bool {
+        self.shared
+            .trades
+            .lock()
+            .unwrap()
+            .remove(trade_id)
+            .is_some()
+    }
+
+    /// Subscribes to the events of every tracked trade.
+    ///
+    /// Only the events published after the subscription are received. A subscriber that falls
+    /// more than the capacity of the tracker behind receives `RecvError::Lagged` and skips the
+    /// oldest events.
+    pub fn subscribe(&self) -> broadcast::Receiver<TradeEvent> {
+        self.shared.events.subscribe()
+    }
+
+    /// Returns the trades that did not reach a terminal status yet, in the order they were tracked.
+    pub fn pending_trades(&self) -> Vec<PendingTrade> {
+        let mut pending: Vec<PendingTrade> = self
+            .shared
+            .trades
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(trade_id, trade)| PendingTrade {
+                trade_id: trade_id.clone(),
+                status: trade.status.clone(),
+                submitted: true,
+                tracked_at: trade.tracked_at,
+            })
+            .collect();
+        pending.sort_by(|a, b| {
+            a.tracked_at
+                .cmp(&b.tracked_at)
+                .then_with(|| a.trade_id.cmp(&b.trade_id))
+        });
+        pending
+    }
+
+    /// Stops the background task. The tracked trades are not checked anymore.
+    pub fn stop(&self) {
+        self.cancellation_token.cancel();
+    }
+}
+
+/// Drop implementation for the tracker.
+///
+/// This implementation stops the background task.
+impl Drop for TradeTracker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Trade tracker builder
+///
+/// This struct is used to build a `TradeTracker` instance.
+pub struct TradeTrackerBuilder {
+    client: Client,
+    interval: Duration,
+    batch_size: usize,
+    capacity: usize,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl TradeTrackerBuilder {
+    pub fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            interval: Duration::from_millis(DEFAULT_INTERVAL_MILLIS),
+            batch_size: DEFAULT_BATCH_SIZE,
+            capacity: DEFAULT_CAPACITY,
+            cancellation_token: None,
+        }
+    }
+
+    /// Sets the interval between two rounds of checks (500 milliseconds by default).
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the maximum number of trades checked in one batch (32 by default).
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets the number of events kept for slow subscribers (1024 by default).
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Sets a token that stops the tracker when cancelled.
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Builds the tracker and starts its background task.
+    pub fn build(self) -> TradeTracker {
+        let (events, _) = broadcast::channel(self.capacity);
+        let shared = Arc::new(Shared {
+            trades: Mutex::new(HashMap::new()),
+            events,
+        });
+        let cancellation_token = self
+            .cancellation_token
+            .map(|token| token.child_token())
+            .unwrap_or_default();
+        tokio::spawn(run(
+            self.client,
+            shared.clone(),
+            self.interval,
+            self.batch_size,
+            cancellation_token.clone(),
+        ));
+        TradeTracker {
+            shared,
+            cancellation_token,
+        }
+    }
+}
+
+/// Checks the tracked trades in batches until the token is cancelled.
+async fn run(
+    client: Client,
+    shared: Arc<Shared>,
+    interval: Duration,
+    batch_size: usize,
+    cancellation_token: CancellationToken,
+) {
+    let round = async {
+        loop {
+            let trade_ids = shared.trade_ids();
+            for batch in trade_ids.chunks(batch_size) {
+                let trade_ids: Vec<&str> = batch.iter().map(String::as_str).collect();
+                let responses = match client.check_trade_statuses(&trade_ids).await {
+                    Ok(responses) => responses,
+                    Err(e) => {
+                        warn!(
+                            "Failed to check the status of {} trades: {}",
+                            batch.len(),
+                            e
+                        );
+                        continue;
+                    }
+                };
+                for (trade_id, response) in batch.iter().zip(responses) {
+                    if let Some(status) = shared.record(trade_id, response) {
+                        client
+                            .journal(TradeRecord::status_changed(trade_id, &status))
+                            .await;
+                    }
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    };
+    tokio::select! {
+        _ = cancellation_token.cancelled() => debug!("Trade tracker stopped"),
+        _ = round => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock::MockService;
+
+    #[tokio::test(start_paused = true)]
+    /// Test trade tracker
+    ///
+    /// This is used to test that the transitions of every tracked trade are published on the
+    /// same channel and that terminal trades stop being tracked.
+    async fn test_tracker_publishes_transitions() {
+        let service =
+            MockService::with_statuses("trade-1", &[TradeStatus::Signed, TradeStatus::Settled]);
+        service.script(
+            "trade-2",
+            &[
+                TradeStatus::Signed,
+                TradeStatus::Confirmed,
+                TradeStatus::Confirmed,
+            ],
+        );
+        let (client, _) = service.into_client();
+        let tracker = TradeTracker::builder(&client).build();
+        let mut events = tracker.subscribe();
+
+        assert!(tracker.track("trade-1"));
+        assert!(tracker.track("trade-2"));
+        assert!(!tracker.track("trade-1"));
+
+        let mut terminal = None;
+        let mut transitions = Vec::new();
+        while terminal.is_none() {
+            match events.recv().await.unwrap() {
+                TradeEvent::StatusChanged(transition) => transitions.push(transition),
+                TradeEvent::Terminal { response, .. } => terminal = Some(response),
+                event => panic!("unexpected event {:?}", event),
+            }
+        }
+
+        assert_eq!(terminal.unwrap().trade_id, "trade-1");
+        assert_eq!(
+            transitions
+                .iter()
+                .filter(|transition| transition.trade_id == "trade-1")
+                .map(|transition| transition.to.clone())
+                .collect::<Vec<_>>(),
+            vec![TradeStatus::Signed, TradeStatus::Settled]
+        );
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let pending = tracker.pending_trades();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].trade_id, "trade-2");
+        assert_eq!(pending[0].status, Some(TradeStatus::Confirmed));
+    }
+}
@@ -0,0 +1,877 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::{sync::atomic::Ordering, time::SystemTime};
+
+use futures::{Stream, StreamExt, future, stream};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tonic::Code;
+use tracing::*;
+
+use crate::{
+    client::{Client, TradeEventStream, TradeStatusStream},
+    core::store::TradeRecord,
+    error::{Error, Result, StopReason},
+    models::{
+        CheckTradeStatusRequest, CheckTradeStatusResponse, PhaseTiming, PhaseTimings, PollStrategy,
+        SendSignedTransactionAndCheckStatusRequest, StatusTransition, TradeEvent, TradeStatus,
+    },
+};
+
+/// Options of a trade status watcher.
+#[derive(Debug, Clone)]
+pub(crate) struct WatchOptions {
+    /// The strategy used when the status is polled.
+    pub(crate) poll_strategy: PollStrategy,
+    /// The maximum number of checks after the first one when the status is polled. It does not
+    /// apply to the server streaming call, whose resubscriptions follow the retry policy.
+    pub(crate) max_attempts: Option<u32>,
+}
+
+/// Transition tracker
+///
+/// This is used to turn the statuses observed for a trade into transitions, and to measure the
+/// time spent in each status.
+pub(crate) struct TransitionTracker {
+    trade_id: String,
+    current: Option<TradeStatus>,
+    since: Instant,
+    timings: PhaseTimings,
+}
+
+impl TransitionTracker {
+    pub(crate) fn new(trade_id: &str) -> Self {
+        Self {
+            trade_id: trade_id.to_string(),
+            current: None,
+            since: Instant::now(),
+            timings: PhaseTimings::default(),
+        }
+    }
+
+    /// Records an observed status.
+    ///
+    /// Returns the transition if the status differs from the previous one, `None` otherwise.
+    pub(crate) fn observe(&mut self, status: &TradeStatus) -> Option<StatusTransition> {
+        if self.current.as_ref() == Some(status) {
+            return None;
+        }
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.since);
+        let from = self.current.replace(status.clone());
+        if let Some(from) = &from {
+            self.timings.phases.push(PhaseTiming {
+                status: from.clone(),
+                duration: elapsed,
+            });
+        }
+        self.since = now;
+        Some(StatusTransition {
+            trade_id: self.trade_id.clone(),
+            from,
+            to: status.clone(),
+            at: SystemTime::now(),
+            elapsed,
+        })
+    }
+
+    /// Returns the time spent in each status so far.
+    pub(crate) fn timings(&self) -> &PhaseTimings {
+        &self.timings
+    }
+}
+
+/// Watch trade status
+///
+/// This is used to build the stream of status updates for a trade. The server streaming call is
+/// used when available, otherwise the status is polled following the poll strategy.
+/// Only status changes are yielded. The stream ends after the first terminal status or the first error.
+///
+/// When the deadline of the request expires or its cancellation token is cancelled, the stream
+/// yields `Error::WatchStopped` with the last observed status.
+pub(crate) fn watch_trade_status(
+    client: Client,
+    request: CheckTradeStatusRequest,
+    options: WatchOptions,
+) -> TradeStatusStream {
+    let deadline = request.deadline;
+    let cancellation_token = request.cancellation_token.clone();
+    let journal = client.clone();
+    let stream = subscribe_trade_status(client, request, options);
+    until_terminal(stream, deadline, cancellation_token)
+        .then(move |item| {
+            let client = journal.clone();
+            async move {
+                if let Ok(response) = &item {
+                    client
+                        .journal(TradeRecord::status_changed(
+                            &response.trade_id,
+                            &response.status,
+                        ))
+                        .await;
+                }
+                item
+            }
+        })
+        .boxed()
+}
+
+/// Subscribe trade status
+///
+/// This is used to build the stream of statuses sent by the server for a trade. When the server
+/// answers `Unimplemented`, the client remembers it and the status is polled instead, for this
+/// trade and every following one.
+///
+/// `max_attempts` of the options only limits the polling. An interrupted stream is opened again
+/// up to `max_retries` times of the retry policy, see `resubscribe_on_failure`.
+fn subscribe_trade_status(
+    client: Client,
+    request: CheckTradeStatusRequest,
+    options: WatchOptions,
+) -> TradeStatusStream {
+    if client.streaming_unsupported.load(Ordering::Relaxed) {
+        return poll_trade_status(client, request, options);
+    }
+    stream::once(async move {
+        match client.service.watch_trade_status(request.clone()).await {
+            Ok(stream) => resubscribe_on_failure(client, request, stream),
+            Err(e) if e.code() == Some(Code::Unimplemented) => {
+                debug!(
+                    "Trade status streaming is not supported by the server, polling with {:?}",
+                    options.poll_strategy
+                );
+                client.streaming_unsupported.store(true, Ordering::Relaxed);
+                poll_trade_status(client, request, options)
+            }
+            Err(e) => stream::once(future::ready(Err(e))).boxed(),
+        }
+    })
+    .flatten()
+    .boxed()
+}
+
+/// Resubscribe on failure
+///
+/// This is used to forward the statuses of an open stream. When the stream fails with a status
+/// code retryable according to the retry policy of the client, it is opened again after the
+/// backoff of the policy, up to `max_retries` times without receiving a status.
+fn resubscribe_on_failure(
+    client: Client,
+    request: CheckTradeStatusRequest,
+    stream: TradeStatusStream,
+) -> TradeStatusStream {
+    stream::unfold(Some((stream, 0)), move |state| {
+        let client = client.clone();
+        let request = request.clone();
+        async move {
+            let (mut stream, mut retries) = state?;
+            loop {
+                match stream.next().await {
+                    Some(Ok(response)) => return Some((Ok(response), Some((stream, 0)))),
+                    Some(Err(e))
+                        if retries < client.retry_policy.max_retries
+                            && e.code()
+                                .is_some_and(|code| client.retry_policy.is_retryable(code)) =>
+                    {
+                        let backoff = client.retry_policy.backoff(retries);
+                        retries += 1;
+                        warn!(
+                            "Trade status stream of {} failed: {}, resubscribing {}/{} in {:?}",
+                            request.trade_id, e, retries, client.retry_policy.max_retries, backoff
+                        );
+                        tokio::time::sleep(backoff).await;
+                        match client.service.watch_trade_status(request.clone()).await {
+                            Ok(resubscribed) => stream = resubscribed,
+                            Err(e) => return Some((Err(e), None)),
+                        }
+                    }
+                    Some(Err(e)) => return Some((Err(e), None)),
+                    None => return None,
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Poll trade status
+///
+/// This is used to build a stream that checks the status of a trade following the poll strategy.
+/// Once `max_attempts` checks followed the first one, the stream yields `Error::WatchStopped`.
+fn poll_trade_status(
+    client: Client,
+    request: CheckTradeStatusRequest,
+    options: WatchOptions,
+) -> TradeStatusStream {
+    let state = PollState {
+        attempts: 0,
+        checks: 0,
+        status: None,
+    };
+    stream::unfold(state, move |mut state| {
+        let client = client.clone();
+        let request = request.clone();
+        let options = options.clone();
+        async move {
+            if state.attempts > 0 {
+                if options.max_attempts.is_some_and(|max| state.attempts > max) {
+                    let stopped = Error::WatchStopped {
+                        reason: StopReason::MaxAttemptsReached,
+                        last_response: None,
+                    };
+                    return Some((Err(stopped), state));
+                }
+                let interval = options
+                    .poll_strategy
+                    .interval(state.checks, state.status.as_ref());
+                tokio::time::sleep(interval).await;
+            }
+            let response = client.check_trade_status(request).await;
+            state.attempts += 1;
+            match &response {
+                Ok(response) if state.status.as_ref() == Some(&response.status) => {
+                    state.checks += 1;
+                }
+                Ok(response) => {
+                    state.status = Some(response.status.clone());
+                    state.checks = 1;
+                }
+                Err(_) => state.checks += 1,
+            }
+            Some((response, state))
+        }
+    })
+    .boxed()
+}
+
+/// State of the trade status polling.
+struct PollState {
+    /// The number of checks done.
+    attempts: u32,
+    /// The number of checks done since the status last changed.
+    checks: u32,
+    /// The last observed status.
+    status: Option<TradeStatus>,
+}
+
+/// Submit and watch
+///
+/// This is used to send a signed transaction and then watch the status of the trade.
+/// The stream yields `TradeEvent::Submitted` first, then a `TradeEvent::StatusChanged` for every
+/// transition and finally `TradeEvent::Terminal`. It ends after the first error.
+///
+/// If the service could not send the transaction, the stream only yields `Error::BlockhashExpired`
+/// when its blockhash expired, `Error::SendFailed` with the error logs otherwise.
+pub(crate) fn submit_and_watch(
+    client: Client,
+    request: SendSignedTransactionAndCheckStatusRequest,
+    options: WatchOptions,
+) -> TradeEventStream {
+    stream::once(async move {
+        match client.send_signed_transaction(request.clone().into()).await {
+            Ok(response) if !response.success => {
+                let error = if response.is_blockhash_expired() {
+                    Error::BlockhashExpired { refreshes: 0 }
+                } else {
+                    Error::SendFailed {
+                        trade_id: response.trade_id,
+                        error_logs: response.error_logs,
+                    }
+                };
+                stream::once(future::ready(Err(error))).boxed()
+            }
+            Ok(response) => {
+                let updates = watch_trade_events(client, request.into(), options);
+                stream::once(future::ready(Ok(TradeEvent::Submitted(response))))
+                    .chain(updates)
+                    .boxed()
+            }
+            Err(e) => stream::once(future::ready(Err(e))).boxed(),
+        }
+    })
+    .flatten()
+    .boxed()
+}
+
+/// Watch trade events
+///
+/// This is used to build the stream of events of a trade from its status updates: a
+/// `TradeEvent::StatusChanged` for every transition, then `TradeEvent::Terminal`.
+pub(crate) fn watch_trade_events(
+    client: Client,
+    request: CheckTradeStatusRequest,
+    options: WatchOptions,
+) -> TradeEventStream {
+    let mut tracker = TransitionTracker::new(&request.trade_id);
+    watch_trade_status(client, request, options)
+        .flat_map(move |item| {
+            let events = match item {
+                Ok(response) => transition_events(&mut tracker, response),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(events)
+        })
+        .boxed()
+}
+
+/// Transition events
+///
+/// This is used to turn an observed status into the events to report: a `TradeEvent::StatusChanged`
+/// if the status changed, or a `TradeEvent::Anomaly` if the trade cannot move to it, followed by
+/// `TradeEvent::Terminal` if the status is terminal.
+pub(crate) fn transition_events(
+    tracker: &mut TransitionTracker,
+    response: CheckTradeStatusResponse,
+) -> Vec<Result<TradeEvent>> {
+    let mut events = Vec::new();
+    if let Some(transition) = tracker.observe(&response.status) {
+        let expected = transition
+            .from
+            .as_ref()
+            .is_none_or(|from| from.can_transition_to(&transition.to));
+        if expected {
+            events.push(Ok(TradeEvent::StatusChanged(transition)));
+        } else {
+            warn!(
+                "Trade {} reported an unexpected transition from {:?} to {:?}",
+                transition.trade_id, transition.from, transition.to
+            );
+            events.push(Ok(TradeEvent::Anomaly(transition)));
+        }
+    }
+    if response.status.is_terminal() {
+        events.push(Ok(TradeEvent::Terminal {
+            response,
+            timings: tracker.timings().clone(),
+        }));
+    }
+    events
+}
+
+/// Skips repeated statuses and ends the stream after the first terminal status or the first error.
+///
+/// The inner stream is dropped as soon as it is done, so no further call is made.
+/// If the inner stream ends before a terminal status, `Error::StreamEnded` is yielded.
+/// If the deadline expires or the token is cancelled first, `Error::WatchStopped` is yielded.
+fn until_terminal<S>(
+    stream: S,
+    deadline: Option<std::time::Instant>,
+    cancellation_token: Option<CancellationToken>,
+) -> impl Stream<Item = Result<CheckTradeStatusResponse>>
+where
+    S: Stream<Item = Result<CheckTradeStatusResponse>> + Unpin,
+{
+    let stopped = move |reason, last_response| Error::WatchStopped {
+        reason,
+        last_response,
+    };
+    stream::unfold(Some((stream, None)), move |state| {
+        let cancellation_token = cancellation_token.clone();
+        async move {
+            let (mut stream, mut last): (S, Option<CheckTradeStatusResponse>) = state?;
+            loop {
+                let item = tokio::select! {
+                    biased;
+                    _ = cancelled(&cancellation_token) => {
+                        Err(stopped(StopReason::Cancelled, last.clone()))
+                    }
+                    _ = expired(deadline) => {
+                        Err(stopped(StopReason::DeadlineExceeded, last.clone()))
+                    }
+                    item = stream.next() => item.unwrap_or(Err(Error::StreamEnded)),
+                };
+                let item = match item {
+                    Ok(response)
+                        if last.as_ref().map(|last| &last.status) == Some(&response.status) =>
+                    {
+                        continue;
+                    }
+                    Ok(response) => {
+                        last = Some(response.clone());
+                        Ok(response)
+                    }
+                    Err(Error::WatchStopped {
+                        reason,
+                        last_response: None,
+                    }) => Err(stopped(reason, last.clone())),
+                    Err(e) => Err(e),
+                };
+                let done = match &item {
+                    Ok(response) => response.status.is_terminal(),
+                    Err(_) => true,
+                };
+                return Some((item, if done { None } else { Some((stream, last)) }));
+            }
+        }
+    })
+}
+
+/// Completes when the token is cancelled, never if there is no token.
+async fn cancelled(cancellation_token: &Option<CancellationToken>) {
+    match cancellation_token {
+        Some(cancellation_token) => cancellation_token.cancelled().await,
+        None => future::pending().await,
+    }
+}
+
+/// Completes when the deadline expires, never if there is no deadline.
+async fn expired(deadline: Option<std::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(Instant::from_std(deadline)).await,
+        None => future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::client::mock::MockService;
+
+    #[tokio::test(start_paused = true)]
+    /// Test polling fallback
+    ///
+    /// This is used to test that the status is polled when the server does not support streaming,
+    /// and that the stream ends on the terminal status.
+    async fn test_watch_falls_back_to_polling() {
+        let (client, service) = MockService::with_statuses(
+            "trade",
+            &[
+                TradeStatus::Signed,
+                TradeStatus::Confirmed,
+                TradeStatus::Settled,
+            ],
+        )
+        .into_client();
+
+        let statuses: Vec<TradeStatus> = client
+            .watch_trade("trade")
+            .map(|response| response.unwrap().status)
+            .collect()
+            .await;
+
+        assert_eq!(
+            statuses,
+            vec![
+                TradeStatus::Signed,
+                TradeStatus::Confirmed,
+                TradeStatus::Settled
+            ]
+        );
+        assert_eq!(*service.status_calls.lock().unwrap(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test streaming fallback is cached
+    ///
+    /// This is used to test that streaming is not tried again once the server answered
+    /// `Unimplemented`.
+    async fn test_streaming_fallback_is_cached() {
+        let (client, service) =
+            MockService::with_statuses("trade", &[TradeStatus::Settled]).into_client();
+
+        for _ in 0..2 {
+            let statuses: Vec<TradeStatus> = client
+                .watch_trade("trade")
+                .map(|response| response.unwrap().status)
+                .collect()
+                .await;
+            assert_eq!(statuses, vec![TradeStatus::Settled]);
+        }
+
+        assert_eq!(*service.watch_calls.lock().unwrap(), 1);
+        assert_eq!(*service.status_calls.lock().unwrap(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test resubscription
+    ///
+    /// This is used to test that a stream interrupted by a transient failure is opened again,
+    /// that the repeated status is skipped and that other failures end the stream.
+    async fn test_stream_resubscribes_on_transient_failure() {
+        let service = MockService::default();
+        service.script_stream(&[Ok(TradeStatus::Signed), Err(Code::Unavailable)]);
+        service.script_stream(&[Ok(TradeStatus::Signed), Ok(TradeStatus::Settled)]);
+        service.script_stream(&[Ok(TradeStatus::Signed), Err(Code::PermissionDenied)]);
+        let (client, service) = service.into_client();
+
+        let statuses: Vec<TradeStatus> = client
+            .watch_trade("trade")
+            .map(|response| response.unwrap().status)
+            .collect()
+            .await;
+        assert_eq!(statuses, vec![TradeStatus::Signed, TradeStatus::Settled]);
+        assert_eq!(*service.watch_calls.lock().unwrap(), 2);
+
+        let items: Vec<_> = client.watch_trade("trade").collect().await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[1].as_ref().unwrap_err().code(),
+            Some(Code::PermissionDenied)
+        );
+        assert_eq!(*service.watch_calls.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    /// Test errors end the stream
+    ///
+    /// This is used to test that the stream yields the error and then ends.
+    async fn test_watch_ends_on_error() {
+        let (client, _) = MockService::default().into_client();
+
+        let items: Vec<_> = client.watch_trade("unknown").collect().await;
+
+        assert_eq!(items.len(), 1);
+        assert!(items[0].as_ref().unwrap_err().is_not_found());
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test submit and watch events
+    ///
+    /// This is used to test the order of the events and that a dropped receiver is ignored.
+    async fn test_submit_and_watch_events() {
+        let (client, _) =
+            MockService::with_statuses("trade", &[TradeStatus::Signed, TradeStatus::Failed])
+                .into_client();
+        let (tx_status, rx_status) = tokio::sync::mpsc::channel(1);
+        drop(rx_status);
+        let request = SendSignedTransactionAndCheckStatusRequest::builder("tx", "trade")
+            .tx_status(tx_status)
+            .build();
+
+        let events: Vec<TradeEvent> = client
+            .send_signed_transaction_and_watch(request.clone())
+            .map(|event| event.unwrap())
+            .collect()
+            .await;
+
+        assert!(matches!(events[0], TradeEvent::Submitted(_)));
+        assert!(matches!(
+            &events[1],
+            TradeEvent::StatusChanged(StatusTransition {
+                from: None,
+                to: TradeStatus::Signed,
+                ..
+            })
+        ));
+        assert!(matches!(
+            &events[2],
+            TradeEvent::StatusChanged(StatusTransition {
+                from: Some(TradeStatus::Signed),
+                to: TradeStatus::Failed,
+                ..
+            })
+        ));
+        assert!(matches!(events[3], TradeEvent::Terminal { .. }));
+        assert_eq!(events.len(), 4);
+
+        let (client, _) = MockService::with_statuses("trade", &[TradeStatus::Failed]).into_client();
+        let response = client
+            .send_signed_transaction_and_check_status(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status, TradeStatus::Failed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test failed send
+    ///
+    /// This is used to test that a transaction the service could not send ends the stream with
+    /// an error, without watching the trade.
+    async fn test_submit_and_watch_stops_on_failed_send() {
+        let (client, service) =
+            MockService::with_statuses("trade", &[TradeStatus::Signed]).into_client();
+        service.fail_send(&["Blockhash not found"]);
+        service.fail_send(&["insufficient funds"]);
+        let request = SendSignedTransactionAndCheckStatusRequest::builder("tx", "trade").build();
+
+        let items: Vec<_> = client
+            .send_signed_transaction_and_watch(request.clone())
+            .collect()
+            .await;
+        assert_eq!(items.len(), 1);
+        assert!(matches!(
+            items[0],
+            Err(Error::BlockhashExpired { refreshes: 0 })
+        ));
+
+        let result = client
+            .send_signed_transaction_and_check_status(request)
+            .await;
+        assert!(matches!(result, Err(Error::SendFailed { .. })));
+        assert_eq!(*service.status_calls.lock().unwrap(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test dropped status receiver
+    ///
+    /// This is used to test that the status loop ignores a dropped receiver, like
+    /// `send_signed_transaction_and_check_status`.
+    async fn test_status_loop_ignores_dropped_receiver() {
+        let (client, _) =
+            MockService::with_statuses("trade", &[TradeStatus::Signed, TradeStatus::Settled])
+                .into_client();
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        drop(rx);
+
+        let response = client
+            .check_trade_status_loop(
+                CheckTradeStatusRequest::builder("trade").build(),
+                Some(tx),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, TradeStatus::Settled);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test transitions only
+    ///
+    /// This is used to test that repeated statuses are reported once and that the time spent in
+    /// each phase is measured.
+    async fn test_only_transitions_are_reported() {
+        let (client, _) = MockService::with_statuses(
+            "trade",
+            &[
+                TradeStatus::Signed,
+                TradeStatus::Signed,
+                TradeStatus::Signed,
+                TradeStatus::Confirmed,
+                TradeStatus::Settled,
+            ],
+        )
+        .into_client();
+        let request = SendSignedTransactionAndCheckStatusRequest::builder("tx", "trade").build();
+
+        let events: Vec<TradeEvent> = client
+            .send_signed_transaction_and_watch(request)
+            .map(|event| event.unwrap())
+            .collect()
+            .await;
+
+        let transitions: Vec<TradeStatus> = events
+            .iter()
+            .filter_map(|event| match event {
+                TradeEvent::StatusChanged(transition) => Some(transition.to.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                TradeStatus::Signed,
+                TradeStatus::Confirmed,
+                TradeStatus::Settled
+            ]
+        );
+        let Some(TradeEvent::Terminal { timings, .. }) = events.last() else {
+            panic!("missing terminal event");
+        };
+        assert_eq!(
+            timings.duration(&TradeStatus::Signed),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            timings.duration(&TradeStatus::Confirmed),
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test deadline
+    ///
+    /// This is used to test that the watcher stops at the deadline with the last observed status.
+    async fn test_watch_stops_at_deadline() {
+        let (client, _) =
+            MockService::with_statuses("trade", &[TradeStatus::Confirmed]).into_client();
+        let request = CheckTradeStatusRequest::builder("trade")
+            .timeout(Duration::from_secs(2))
+            .build();
+
+        let result = client
+            .check_trade_status_loop(request, None, None, None)
+            .await;
+
+        let Err(error) = result else {
+            panic!("the watcher should stop");
+        };
+        assert!(matches!(
+            error,
+            Error::WatchStopped {
+                reason: StopReason::DeadlineExceeded,
+                ..
+            }
+        ));
+        assert_eq!(
+            error.last_response().unwrap().status,
+            TradeStatus::Confirmed
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test cancellation
+    ///
+    /// This is used to test that the watcher stops when the token is cancelled.
+    async fn test_watch_stops_on_cancellation() {
+        let (client, _) = MockService::with_statuses("trade", &[TradeStatus::Signed]).into_client();
+        let token = CancellationToken::new();
+        let request = CheckTradeStatusRequest::builder("trade")
+            .cancellation_token(token.clone())
+            .build();
+
+        let mut updates = client.watch_trade_status(request);
+        assert_eq!(
+            updates.next().await.unwrap().unwrap().status,
+            TradeStatus::Signed
+        );
+        token.cancel();
+        let error = updates.next().await.unwrap().unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::WatchStopped {
+                reason: StopReason::Cancelled,
+                ..
+            }
+        ));
+        assert!(updates.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test max attempts
+    ///
+    /// This is used to test that polling stops after the maximum number of attempts.
+    async fn test_polling_stops_after_max_attempts() {
+        let (client, service) =
+            MockService::with_statuses("trade", &[TradeStatus::Signed]).into_client();
+
+        let result = client
+            .check_trade_status_loop(
+                CheckTradeStatusRequest::builder("trade").build(),
+                None,
+                None,
+                Some(2),
+            )
+            .await;
+
+        let Err(Error::WatchStopped {
+            reason,
+            last_response,
+        }) = result
+        else {
+            panic!("the watcher should stop");
+        };
+        assert_eq!(reason, StopReason::MaxAttemptsReached);
+        assert_eq!(last_response.unwrap().status, TradeStatus::Signed);
+        assert_eq!(*service.status_calls.lock().unwrap(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test poll strategy
+    ///
+    /// This is used to test that the watcher waits the interval of the last observed status.
+    async fn test_polling_follows_poll_strategy() {
+        let (client, _) = MockService::with_statuses(
+            "trade",
+            &[
+                TradeStatus::Signed,
+                TradeStatus::Signed,
+                TradeStatus::Confirmed,
+                TradeStatus::Settled,
+            ],
+        )
+        .into_client();
+        let request = SendSignedTransactionAndCheckStatusRequest::builder("tx", "trade")
+            .poll_strategy(PollStrategy::PhaseAware {
+                intervals: vec![
+                    (TradeStatus::Signed, Duration::from_millis(100)),
+                    (TradeStatus::Confirmed, Duration::from_secs(2)),
+                ],
+                default: Duration::from_millis(500),
+            })
+            .build();
+
+        let events: Vec<TradeEvent> = client
+            .send_signed_transaction_and_watch(request)
+            .map(|event| event.unwrap())
+            .collect()
+            .await;
+
+        let Some(TradeEvent::Terminal { timings, .. }) = events.last() else {
+            panic!("missing terminal event");
+        };
+        assert_eq!(
+            timings.duration(&TradeStatus::Signed),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            timings.duration(&TradeStatus::Confirmed),
+            Some(Duration::from_secs(2))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test anomaly
+    ///
+    /// This is used to test that an impossible transition is reported as an anomaly and that the
+    /// watcher follows the reported status.
+    async fn test_impossible_transition_is_an_anomaly() {
+        let (client, _) = MockService::with_statuses(
+            "trade",
+            &[
+                TradeStatus::Confirmed,
+                TradeStatus::Signed,
+                TradeStatus::Settled,
+            ],
+        )
+        .into_client();
+        let request = SendSignedTransactionAndCheckStatusRequest::builder("tx", "trade").build();
+
+        let events: Vec<TradeEvent> = client
+            .send_signed_transaction_and_watch(request)
+            .map(|event| event.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 5);
+        assert!(matches!(
+            &events[2],
+            TradeEvent::Anomaly(StatusTransition {
+                from: Some(TradeStatus::Confirmed),
+                to: TradeStatus::Signed,
+                ..
+            })
+        ));
+        assert!(matches!(events[3], TradeEvent::StatusChanged(_)));
+        assert!(matches!(events[4], TradeEvent::Terminal { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    /// Test unknown status
+    ///
+    /// This is used to test that the watcher keeps following a trade in an unknown status.
+    async fn test_watch_continues_on_unknown_status() {
+        let (client, _) =
+            MockService::with_statuses("trade", &[TradeStatus::Unknown(7), TradeStatus::Settled])
+                .into_client();
+
+        let statuses: Vec<TradeStatus> = client
+            .watch_trade("trade")
+            .map(|response| response.unwrap().status)
+            .collect()
+            .await;
+
+        assert_eq!(
+            statuses,
+            vec![TradeStatus::Unknown(7), TradeStatus::Settled]
+        );
+    }
+}
@@ -4,4 +4,14 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
+pub mod auth;
 pub mod config;
+#[cfg(feature = "solana")]
+pub mod describe;
+pub mod retry;
+pub mod signer;
+pub mod store;
+#[cfg(feature = "solana")]
+pub mod transaction;
+#[cfg(feature = "solana")]
+pub mod verify;
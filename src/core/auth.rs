@@ -0,0 +1,195 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use tonic::{
+    Request, Status,
+    metadata::{AsciiMetadataKey, AsciiMetadataValue},
+    service::Interceptor,
+};
+
+use crate::error::{Error, Result};
+
+/// The metadata key used to send the API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The metadata key used to send the bearer token.
+pub const AUTHORIZATION_HEADER: &str = "authorization";
+
+/// Token provider
+///
+/// This trait is used to supply the bearer token attached to every call, which makes it possible
+/// to rotate tokens without recreating the client. It is called before each call, so
+/// implementations should cache the token and refresh it in the background when needed.
+pub trait TokenProvider: Send + Sync {
+    /// Returns the token to send, without the `Bearer ` prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no valid token is available. The call is then failed with
+    /// an `Unauthenticated` status without being sent.
+    fn token(&self) -> Result<String>;
+}
+
+/// Static token provider
+///
+/// This struct is used to send the same bearer token on every call.
+#[derive(Clone)]
+pub struct StaticTokenProvider {
+    token: String,
+}
+
+impl StaticTokenProvider {
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+        }
+    }
+}
+
+impl TokenProvider for StaticTokenProvider {
+    fn token(&self) -> Result<String> {
+        Ok(self.token.clone())
+    }
+}
+
+/// Credentials
+///
+/// This struct holds the credentials and custom metadata attached to every call.
+/// The values are never printed by the `Debug` implementation.
+#[derive(Clone, Default)]
+pub struct Credentials {
+    pub(crate) api_key: Option<AsciiMetadataValue>,
+    pub(crate) metadata: Vec<(AsciiMetadataKey, AsciiMetadataValue)>,
+    pub(crate) token_provider: Option<Arc<dyn TokenProvider>>,
+}
+
+impl Credentials {
+    /// Sets the API key, sent in the `x-api-key` metadata entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API key is not a valid metadata value.
+    pub(crate) fn set_api_key(&mut self, api_key: &str) -> Result<()> {
+        let mut value = AsciiMetadataValue::from_str(api_key)
+            .map_err(|_| Error::InvalidConfig("Invalid API key".to_string()))?;
+        value.set_sensitive(true);
+        self.api_key = Some(value);
+        Ok(())
+    }
+
+    /// Adds a custom metadata entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key or the value are not valid metadata.
+    pub(crate) fn add_metadata(&mut self, key: &str, value: &str) -> Result<()> {
+        let key = AsciiMetadataKey::from_str(key)
+            .map_err(|e| Error::InvalidConfig(format!("Invalid metadata key {}: {}", key, e)))?;
+        let value = AsciiMetadataValue::from_str(value).map_err(|e| {
+            Error::InvalidConfig(format!("Invalid metadata value for {}: {}", key, e))
+        })?;
+        self.metadata.push((key, value));
+        Ok(())
+    }
+}
+
+/// Debug implementation for the credentials.
+///
+/// This implementation only reports which credentials are set, never their values.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field(
+                "metadata",
+                &self
+                    .metadata
+                    .iter()
+                    .map(|(key, _)| key.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "token_provider",
+                &self.token_provider.as_ref().map(|_| "<provider>"),
+            )
+            .finish()
+    }
+}
+
+/// Auth interceptor
+///
+/// This interceptor attaches the credentials and the custom metadata to every call.
+#[derive(Clone)]
+pub(crate) struct AuthInterceptor {
+    credentials: Credentials,
+}
+
+impl AuthInterceptor {
+    pub(crate) fn new(credentials: Credentials) -> Self {
+        Self { credentials }
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let metadata = request.metadata_mut();
+        for (key, value) in &self.credentials.metadata {
+            metadata.insert(key.clone(), value.clone());
+        }
+        if let Some(api_key) = &self.credentials.api_key {
+            metadata.insert(API_KEY_HEADER, api_key.clone());
+        }
+        if let Some(provider) = &self.credentials.token_provider {
+            let token = provider
+                .token()
+                .map_err(|e| Status::unauthenticated(e.to_string()))?;
+            let mut value = AsciiMetadataValue::from_str(&format!("Bearer {}", token))
+                .map_err(|_| Status::unauthenticated("Invalid bearer token"))?;
+            value.set_sensitive(true);
+            metadata.insert(AUTHORIZATION_HEADER, value);
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test auth interceptor
+    ///
+    /// This is used to test that the credentials and metadata are attached to the request.
+    fn test_interceptor_attaches_credentials() {
+        let mut credentials = Credentials::default();
+        credentials.set_api_key("partner-key").unwrap();
+        credentials.add_metadata("x-partner-id", "desk-1").unwrap();
+        credentials.token_provider = Some(Arc::new(StaticTokenProvider::new("token")));
+
+        let request = AuthInterceptor::new(credentials)
+            .call(Request::new(()))
+            .unwrap();
+        let metadata = request.metadata();
+
+        assert_eq!(metadata.get(API_KEY_HEADER).unwrap(), "partner-key");
+        assert_eq!(metadata.get("x-partner-id").unwrap(), "desk-1");
+        assert_eq!(metadata.get(AUTHORIZATION_HEADER).unwrap(), "Bearer token");
+    }
+
+    #[test]
+    /// Test invalid metadata
+    ///
+    /// This is used to test that invalid metadata is rejected when configured.
+    fn test_invalid_metadata_is_rejected() {
+        let mut credentials = Credentials::default();
+
+        assert!(credentials.add_metadata("invalid key", "value").is_err());
+        assert!(credentials.add_metadata("x-valid", "line\nbreak").is_err());
+        assert!(format!("{:?}", credentials).contains("metadata: []"));
+    }
+}
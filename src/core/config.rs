@@ -5,8 +5,12 @@
 // https://opensource.org/licenses/MIT.
 
 use crate::client::ClientType;
-use eyre::{Result, eyre};
+use crate::core::auth::{Credentials, TokenProvider};
+use crate::core::retry::RetryPolicy;
+use crate::core::store::TradeStore;
+use crate::error::{Error, Result};
 use std::fmt;
+use std::sync::Arc;
 use url::Url;
 
 /// Represents the network to use for the client.
@@ -47,6 +51,12 @@ pub struct Config {
     /// If `true`, the `network` field will be ignored and the `url` will be
     /// used directly for the connection.
     pub is_final_url: bool,
+    /// The retry policy applied to the calls to the gRPC service.
+    pub retry_policy: RetryPolicy,
+    /// The credentials and custom metadata attached to every call.
+    pub credentials: Credentials,
+    /// The store keeping the trade journal, if any.
+    pub trade_store: Option<Arc<dyn TradeStore>>,
 }
 
 impl Config {
@@ -78,6 +88,14 @@ pub struct ConfigBuilder {
     /// If `true`, the `network` field will be ignored and the `url` will be
     /// used directly for the connection.
     pub is_final_url: bool,
+    /// The retry policy applied to the calls to the gRPC service.
+    ///
+    /// Defaults to `RetryPolicy::default()`.
+    pub retry_policy: RetryPolicy,
+    /// The credentials and custom metadata attached to every call.
+    pub credentials: Credentials,
+    /// The store keeping the trade journal, if any.
+    pub trade_store: Option<Arc<dyn TradeStore>>,
 }
 
 impl ConfigBuilder {
@@ -90,6 +108,9 @@ impl ConfigBuilder {
             url: Url::parse("https://localhost").unwrap(),
             client_type: ClientType::Grpc,
             is_final_url: false,
+            retry_policy: RetryPolicy::default(),
+            credentials: Credentials::default(),
+            trade_store: None,
         }
     }
 
@@ -113,7 +134,7 @@ impl ConfigBuilder {
     ///
     /// Returns an error if the URL is invalid.
     pub fn url(mut self, url: &str) -> Result<Self> {
-        self.url = Url::parse(url).map_err(|e| eyre!("Invalid URL: {}", e))?;
+        self.url = Url::parse(url).map_err(|e| Error::InvalidUri(e.to_string()))?;
         Ok(self)
     }
 
@@ -125,11 +146,77 @@ impl ConfigBuilder {
     /// # Returns
     ///
     /// Returns the `ConfigBuilder` instance.
-    pub fn is_final_url(mut self, is_final_url: bool) -> Self {
+    pub fn final_url(mut self, is_final_url: bool) -> Self {
         self.is_final_url = is_final_url;
         self
     }
 
+    /// Sets the flag to indicate whether the `url` is the final URL to use.
+    #[deprecated(note = "use `final_url` instead")]
+    pub fn is_final_url(self, is_final_url: bool) -> Self {
+        self.final_url(is_final_url)
+    }
+
+    /// Sets the retry policy applied to the calls to the gRPC service.
+    ///
+    /// Use `RetryPolicy::disabled()` to send every call exactly once.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `ConfigBuilder` instance.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the partner API key.
+    ///
+    /// The key is sent in the `x-api-key` metadata entry of every call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the API key is not a valid metadata value.
+    pub fn api_key(mut self, api_key: &str) -> Result<Self> {
+        self.credentials.set_api_key(api_key)?;
+        Ok(self)
+    }
+
+    /// Adds a custom metadata entry sent with every call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key or the value are not valid ASCII metadata.
+    pub fn metadata(mut self, key: &str, value: &str) -> Result<Self> {
+        self.credentials.add_metadata(key, value)?;
+        Ok(self)
+    }
+
+    /// Sets the provider of the bearer token sent with every call.
+    ///
+    /// The token is sent in the `authorization` metadata entry as `Bearer <token>`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `ConfigBuilder` instance.
+    pub fn token_provider(mut self, token_provider: impl TokenProvider + 'static) -> Self {
+        self.credentials.token_provider = Some(Arc::new(token_provider));
+        self
+    }
+
+    /// Sets the store keeping the trade journal.
+    ///
+    /// The client records every unsigned transaction created, every signed transaction sent and
+    /// every status change observed by a watcher, so `Client::resume_pending_trades` can follow
+    /// the pending trades again after a restart.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `ConfigBuilder` instance.
+    pub fn trade_store(mut self, trade_store: impl TradeStore + 'static) -> Self {
+        self.trade_store = Some(Arc::new(trade_store));
+        self
+    }
+
     /// Builds the `Config` instance.
     ///
     /// This is used to build the `Config` instance.
@@ -151,17 +238,19 @@ impl ConfigBuilder {
                 Some(url::Host::Ipv4(ip)) => ip.to_string(),
                 Some(url::Host::Ipv6(ip)) => ip.to_string(),
                 _ => {
-                    return Err(eyre!("Invalid host: no host component found"));
+                    return Err(Error::InvalidConfig(
+                        "Invalid host: no host component found".to_string(),
+                    ));
                 }
             };
 
             dest_url = Url::parse(&format!("{}://{}", self.url.scheme(), new_host))
-                .map_err(|e| eyre!("Invalid URL: {:?}", e))?;
+                .map_err(|e| Error::InvalidUri(e.to_string()))?;
 
             if let Some(port) = self.url.port() {
                 dest_url
                     .set_port(Some(port))
-                    .map_err(|e| eyre!("Failed to set port: {:?}", e))?;
+                    .map_err(|_| Error::InvalidConfig("Failed to set port".to_string()))?;
             }
         }
         Ok(Config {
@@ -169,6 +258,15 @@ impl ConfigBuilder {
             url: dest_url,
             client_type: self.client_type,
             is_final_url: self.is_final_url,
+            retry_policy: self.retry_policy,
+            credentials: self.credentials,
+            trade_store: self.trade_store,
         })
     }
 }
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
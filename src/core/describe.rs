@@ -0,0 +1,522 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::fmt;
+
+use solana_pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::core::{
+    transaction::{
+        ASSOCIATED_TOKEN_PROGRAM, COMPUTE_BUDGET_PROGRAM, SYSTEM_PROGRAM, TOKEN_2022_PROGRAM,
+        TOKEN_PROGRAM,
+    },
+    verify::VerificationPolicy,
+};
+
+/// Transaction description
+///
+/// This struct is used to review an unsigned transaction before signing it, e.g. in an approval
+/// UI or an audit log. Its `Display` renders it over several lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionDescription {
+    /// The account paying the fees, the first required signer.
+    pub fee_payer: Option<Pubkey>,
+    /// The recent blockhash of the transaction.
+    pub recent_blockhash: String,
+    /// The accounts which must sign the transaction.
+    pub required_signers: Vec<Pubkey>,
+    /// The static accounts of the transaction.
+    pub accounts: Vec<AccountDescription>,
+    /// The number of accounts loaded from address lookup tables.
+    pub lookup_table_accounts: usize,
+    /// The programs invoked, in order of first invocation.
+    pub programs: Vec<Pubkey>,
+    /// The instructions of the transaction.
+    pub instructions: Vec<InstructionDescription>,
+    /// The compute budget requested by the transaction.
+    pub compute_budget: ComputeBudget,
+}
+
+/// Account description
+///
+/// This struct is used to describe a static account of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountDescription {
+    /// The address of the account.
+    pub pubkey: Pubkey,
+    /// Whether the account must sign the transaction.
+    pub is_signer: bool,
+    /// Whether the account may be written by the transaction.
+    pub is_writable: bool,
+}
+
+/// Instruction description
+///
+/// This struct is used to describe an instruction of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionDescription {
+    /// The program invoked, `None` if the program index of the instruction is out of range.
+    pub program: Option<Pubkey>,
+    /// The name of the program, if known.
+    pub program_name: Option<String>,
+    /// The name of the instruction, or the hex encoded data if it cannot be decoded.
+    pub name: String,
+    /// The accounts of the instruction, `None` for an account loaded from an address lookup
+    /// table.
+    pub accounts: Vec<Option<Pubkey>>,
+    /// The decoded arguments, as name and value.
+    pub args: Vec<(String, String)>,
+}
+
+/// Compute budget
+///
+/// This struct is used to describe the compute budget instructions of a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// The compute unit limit.
+    pub unit_limit: Option<u32>,
+    /// The compute unit price, in micro lamports.
+    pub unit_price_micro_lamports: Option<u64>,
+    /// The requested heap frame, in bytes.
+    pub heap_frame_bytes: Option<u32>,
+    /// The limit of the loaded accounts data size, in bytes.
+    pub loaded_accounts_data_size_limit: Option<u32>,
+}
+
+/// Describes a transaction.
+///
+/// The compute budget, system, token and associated token account instructions are decoded. The
+/// instructions of the Darklake programs of the policy are named after the discriminators
+/// registered with `VerificationPolicyBuilder::instruction_name`, and their arguments are shown as
+/// little endian `u64` words after the 8 bytes discriminator.
+pub fn describe_transaction(
+    transaction: &VersionedTransaction,
+    policy: Option<&VerificationPolicy>,
+) -> TransactionDescription {
+    let message = &transaction.message;
+    let keys = message.static_account_keys();
+    let accounts: Vec<AccountDescription> = keys
+        .iter()
+        .enumerate()
+        .map(|(index, pubkey)| AccountDescription {
+            pubkey: *pubkey,
+            is_signer: message.is_signer(index),
+            is_writable: message.is_maybe_writable(index, None),
+        })
+        .collect();
+    let required_signers: Vec<Pubkey> = accounts
+        .iter()
+        .filter(|account| account.is_signer)
+        .map(|account| account.pubkey)
+        .collect();
+    let lookup_table_accounts = message
+        .address_table_lookups()
+        .unwrap_or_default()
+        .iter()
+        .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+        .sum();
+
+    let mut programs = Vec::new();
+    let mut instructions = Vec::new();
+    let mut compute_budget = ComputeBudget::default();
+    for instruction in message.instructions() {
+        let program = keys.get(usize::from(instruction.program_id_index)).copied();
+        if let Some(program) = program.filter(|program| !programs.contains(program)) {
+            programs.push(program);
+        }
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|account| keys.get(usize::from(*account)).copied())
+            .collect();
+        let decoded = program
+            .and_then(|program| decode(&program, &instruction.data, policy, &mut compute_budget));
+        let (program_name, name, args) = match decoded {
+            Some((program_name, name, args)) => (Some(program_name.to_string()), name, args),
+            None => (None, hex(&instruction.data), Vec::new()),
+        };
+        instructions.push(InstructionDescription {
+            program,
+            program_name,
+            name,
+            accounts,
+            args,
+        });
+    }
+
+    TransactionDescription {
+        fee_payer: required_signers.first().copied(),
+        recent_blockhash: message.recent_blockhash().to_string(),
+        required_signers,
+        accounts,
+        lookup_table_accounts,
+        programs,
+        instructions,
+        compute_budget,
+    }
+}
+
+/// A decoded instruction: the program name, the instruction name and the arguments.
+type Decoded = (&'static str, String, Vec<(String, String)>);
+
+/// Decodes the instruction of a known program, recording the compute budget settings.
+fn decode(
+    program: &Pubkey,
+    data: &[u8],
+    policy: Option<&VerificationPolicy>,
+    compute_budget: &mut ComputeBudget,
+) -> Option<Decoded> {
+    let arg = |name: &str, value: String| (name.to_string(), value);
+    let named = |program_name, name: &str, args| Some((program_name, name.to_string(), args));
+
+    if *program == COMPUTE_BUDGET_PROGRAM {
+        let program_name = "Compute Budget";
+        let (tag, rest) = data.split_first()?;
+        return match tag {
+            1 => {
+                let bytes = read_u32(rest)?;
+                compute_budget.heap_frame_bytes = Some(bytes);
+                named(
+                    program_name,
+                    "RequestHeapFrame",
+                    vec![arg("bytes", bytes.to_string())],
+                )
+            }
+            2 => {
+                let units = read_u32(rest)?;
+                compute_budget.unit_limit = Some(units);
+                named(
+                    program_name,
+                    "SetComputeUnitLimit",
+                    vec![arg("units", units.to_string())],
+                )
+            }
+            3 => {
+                let price = read_u64(rest)?;
+                compute_budget.unit_price_micro_lamports = Some(price);
+                named(
+                    program_name,
+                    "SetComputeUnitPrice",
+                    vec![arg("micro_lamports", price.to_string())],
+                )
+            }
+            4 => {
+                let bytes = read_u32(rest)?;
+                compute_budget.loaded_accounts_data_size_limit = Some(bytes);
+                named(
+                    program_name,
+                    "SetLoadedAccountsDataSizeLimit",
+                    vec![arg("bytes", bytes.to_string())],
+                )
+            }
+            _ => None,
+        };
+    }
+
+    if *program == SYSTEM_PROGRAM {
+        let program_name = "System";
+        let tag = read_u32(data)?;
+        let rest = &data[4..];
+        return match tag {
+            0 => named(
+                program_name,
+                "CreateAccount",
+                vec![
+                    arg("lamports", read_u64(rest)?.to_string()),
+                    arg("space", read_u64(rest.get(8..)?)?.to_string()),
+                ],
+            ),
+            2 => named(
+                program_name,
+                "Transfer",
+                vec![arg("lamports", read_u64(rest)?.to_string())],
+            ),
+            11 => named(
+                program_name,
+                "TransferWithSeed",
+                vec![arg("lamports", read_u64(rest)?.to_string())],
+            ),
+            _ => None,
+        };
+    }
+
+    if *program == TOKEN_PROGRAM || *program == TOKEN_2022_PROGRAM {
+        let program_name = if *program == TOKEN_PROGRAM {
+            "Token"
+        } else {
+            "Token 2022"
+        };
+        let (tag, rest) = data.split_first()?;
+        return match tag {
+            1 => named(program_name, "InitializeAccount", vec![]),
+            3 => named(
+                program_name,
+                "Transfer",
+                vec![arg("amount", read_u64(rest)?.to_string())],
+            ),
+            7 => named(
+                program_name,
+                "MintTo",
+                vec![arg("amount", read_u64(rest)?.to_string())],
+            ),
+            9 => named(program_name, "CloseAccount", vec![]),
+            12 => named(
+                program_name,
+                "TransferChecked",
+                vec![
+                    arg("amount", read_u64(rest)?.to_string()),
+                    arg("decimals", rest.get(8)?.to_string()),
+                ],
+            ),
+            17 => named(program_name, "SyncNative", vec![]),
+            18 => named(program_name, "InitializeAccount3", vec![]),
+            _ => None,
+        };
+    }
+
+    if *program == ASSOCIATED_TOKEN_PROGRAM {
+        let program_name = "Associated Token Account";
+        return match data.first() {
+            None | Some(0) => named(program_name, "Create", vec![]),
+            Some(1) => named(program_name, "CreateIdempotent", vec![]),
+            _ => None,
+        };
+    }
+
+    let policy = policy.filter(|policy| policy.is_darklake(program))?;
+    let (discriminator, rest) = data.split_at_checked(8)?;
+    let name = policy
+        .instruction_name(discriminator)
+        .map(str::to_string)
+        .unwrap_or_else(|| hex(discriminator));
+    let args = rest
+        .chunks(8)
+        .enumerate()
+        .map(|(index, word)| match read_u64(word) {
+            Some(value) => (format!("arg{}", index), value.to_string()),
+            None => (format!("arg{}", index), hex(word)),
+        })
+        .collect();
+    Some(("Darklake", name, args))
+}
+
+/// Reads a little endian `u32` at the start of the data.
+fn read_u32(data: &[u8]) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(..4)?.try_into().ok()?))
+}
+
+/// Reads a little endian `u64` at the start of the data.
+fn read_u64(data: &[u8]) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(..8)?.try_into().ok()?))
+}
+
+/// Encodes the data in lowercase hex.
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+impl fmt::Display for TransactionDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.fee_payer {
+            Some(fee_payer) => writeln!(f, "Fee payer: {}", fee_payer)?,
+            None => writeln!(f, "Fee payer: none")?,
+        }
+        writeln!(f, "Recent blockhash: {}", self.recent_blockhash)?;
+        writeln!(f, "Required signers:")?;
+        for signer in &self.required_signers {
+            writeln!(f, "  {}", signer)?;
+        }
+        writeln!(f, "Accounts:")?;
+        for (index, account) in self.accounts.iter().enumerate() {
+            let signer = if account.is_signer { "s" } else { "-" };
+            let writable = if account.is_writable { "w" } else { "-" };
+            writeln!(f, "  #{} {}{} {}", index, signer, writable, account.pubkey)?;
+        }
+        if self.lookup_table_accounts > 0 {
+            writeln!(
+                f,
+                "  + {} accounts from address lookup tables",
+                self.lookup_table_accounts
+            )?;
+        }
+        writeln!(f, "Compute budget:")?;
+        let budget = &self.compute_budget;
+        let or_default = |value: Option<String>| value.unwrap_or_else(|| "default".to_string());
+        writeln!(
+            f,
+            "  unit limit: {}",
+            or_default(budget.unit_limit.map(|units| units.to_string()))
+        )?;
+        writeln!(
+            f,
+            "  unit price: {}",
+            or_default(
+                budget
+                    .unit_price_micro_lamports
+                    .map(|price| format!("{} micro lamports", price))
+            )
+        )?;
+        if let Some(bytes) = budget.heap_frame_bytes {
+            writeln!(f, "  heap frame: {} bytes", bytes)?;
+        }
+        if let Some(bytes) = budget.loaded_accounts_data_size_limit {
+            writeln!(f, "  loaded accounts data size limit: {} bytes", bytes)?;
+        }
+        writeln!(f, "Instructions:")?;
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            write!(f, "  #{} ", index)?;
+            match &instruction.program_name {
+                Some(program_name) => write!(f, "{} {}", program_name, instruction.name)?,
+                None => match &instruction.program {
+                    Some(program) => write!(f, "{} data {}", program, instruction.name)?,
+                    None => write!(f, "<invalid program index> data {}", instruction.name)?,
+                },
+            }
+            let args: Vec<String> = instruction
+                .args
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+            if !args.is_empty() {
+                write!(f, " {}", args.join(" "))?;
+            }
+            writeln!(f)?;
+            for account in &instruction.accounts {
+                match account {
+                    Some(pubkey) => writeln!(f, "      {}", pubkey)?,
+                    None => writeln!(f, "      (address lookup table)")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        message::{Message, VersionedMessage},
+        signature::Keypair,
+        signer::Signer,
+    };
+
+    use super::*;
+
+    const DARKLAKE: Pubkey = Pubkey::from_str_const("DarkLake11111111111111111111111111111111111");
+
+    #[test]
+    /// Test transaction description
+    ///
+    /// This is used to test that the compute budget and the Darklake instructions are decoded,
+    /// and that the signers and writable accounts are reported.
+    fn test_describe_transaction() {
+        let user = Keypair::new();
+        let mint = Pubkey::from_str_const("So11111111111111111111111111111111111111112");
+        let mut limit = vec![2];
+        limit.extend(200_000u32.to_le_bytes());
+        let mut price = vec![3];
+        price.extend(5_000u64.to_le_bytes());
+        let mut swap = vec![0xd1; 8];
+        swap.extend(1_000u64.to_le_bytes());
+        swap.extend(900u64.to_le_bytes());
+        let message = Message::new(
+            &[
+                Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM, &limit, vec![]),
+                Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM, &price, vec![]),
+                Instruction::new_with_bytes(
+                    DARKLAKE,
+                    &swap,
+                    vec![
+                        AccountMeta::new(user.pubkey(), true),
+                        AccountMeta::new_readonly(mint, false),
+                    ],
+                ),
+            ],
+            Some(&user.pubkey()),
+        );
+        let transaction = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::Legacy(message),
+        };
+        let policy = VerificationPolicy::builder()
+            .darklake_program(DARKLAKE)
+            .instruction_name([0xd1; 8], "swap")
+            .build()
+            .unwrap();
+
+        let description = describe_transaction(&transaction, Some(&policy));
+        assert_eq!(description.fee_payer, Some(user.pubkey()));
+        assert_eq!(description.required_signers, vec![user.pubkey()]);
+        assert_eq!(description.programs, vec![COMPUTE_BUDGET_PROGRAM, DARKLAKE]);
+        assert_eq!(description.compute_budget.unit_limit, Some(200_000));
+        assert_eq!(
+            description.compute_budget.unit_price_micro_lamports,
+            Some(5_000)
+        );
+        let account = |pubkey| {
+            description
+                .accounts
+                .iter()
+                .find(|account| account.pubkey == pubkey)
+                .unwrap()
+        };
+        assert!(account(user.pubkey()).is_signer && account(user.pubkey()).is_writable);
+        assert!(!account(mint).is_signer && !account(mint).is_writable);
+        let swap = &description.instructions[2];
+        assert_eq!(swap.name, "swap");
+        assert_eq!(
+            swap.args,
+            vec![
+                ("arg0".to_string(), "1000".to_string()),
+                ("arg1".to_string(), "900".to_string())
+            ]
+        );
+        let display = description.to_string();
+        assert!(display.contains("#0 Compute Budget SetComputeUnitLimit units=200000"));
+        assert!(display.contains("#2 Darklake swap arg0=1000 arg1=900"));
+        assert!(display.contains("unit price: 5000 micro lamports"));
+
+        // Without a policy the Darklake instruction is shown as raw data.
+        let description = describe_transaction(&transaction, None);
+        assert_eq!(description.instructions[2].program_name, None);
+        assert!(description.instructions[2].name.starts_with("d1d1"));
+    }
+
+    #[test]
+    /// Test invalid program index
+    ///
+    /// This is used to test that an instruction whose program index is out of range is not
+    /// reported as invoking the system program.
+    fn test_describe_invalid_program_index() {
+        let user = Keypair::new();
+        let mut message = Message::new(
+            &[Instruction::new_with_bytes(
+                SYSTEM_PROGRAM,
+                &[2, 0, 0, 0],
+                vec![AccountMeta::new(user.pubkey(), true)],
+            )],
+            Some(&user.pubkey()),
+        );
+        message.instructions[0].program_id_index = 42;
+        let transaction = VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::Legacy(message),
+        };
+
+        let description = describe_transaction(&transaction, None);
+
+        assert_eq!(description.instructions[0].program, None);
+        assert_eq!(description.instructions[0].program_name, None);
+        assert!(description.programs.is_empty());
+        assert!(
+            description
+                .to_string()
+                .contains("#0 <invalid program index> data 02000000")
+        );
+    }
+}
@@ -0,0 +1,197 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::time::Duration;
+
+use rand::Rng;
+use tonic::Code;
+
+/// Retry policy for the calls to the Darklake Integrations service.
+///
+/// The policy is applied automatically to the idempotent calls (`quote`, `check_trade_status`
+/// and `get_trades_list_by_user`). It is applied to `send_signed_transaction` only when
+/// `retry_send_signed_transaction` is enabled, as the server deduplicates resubmissions using the
+/// `tracking_id`/`trade_id` pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of retries after the first attempt. `0` disables retries.
+    pub max_retries: u32,
+    /// The backoff before the first retry. It doubles on every following retry.
+    pub base_backoff: Duration,
+    /// The upper bound for the backoff between two attempts.
+    pub max_backoff: Duration,
+    /// Whether to randomize the backoff to avoid retry storms.
+    ///
+    /// If `true`, each backoff is a random duration between half and the full computed backoff.
+    pub jitter: bool,
+    /// The gRPC status codes that are considered transient and can be retried.
+    pub retryable_codes: Vec<Code>,
+    /// Whether to retry `send_signed_transaction` as well.
+    pub retry_send_signed_transaction: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            jitter: true,
+            retryable_codes: vec![
+                Code::Unavailable,
+                Code::DeadlineExceeded,
+                Code::ResourceExhausted,
+                Code::Aborted,
+            ],
+            retry_send_signed_transaction: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn builder() -> RetryPolicyBuilder {
+        RetryPolicyBuilder::new()
+    }
+
+    /// Creates a policy that never retries.
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a failure with the given status code can be retried.
+    pub fn is_retryable(&self, code: Code) -> bool {
+        self.retryable_codes.contains(&code)
+    }
+
+    /// Computes the backoff to wait before the given retry.
+    ///
+    /// `retry` is zero based, so `backoff(0)` is the wait before the first retry.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .base_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        if self.jitter && !backoff.is_zero() {
+            let half = backoff / 2;
+            half + rand::rng().random_range(Duration::ZERO..=backoff - half)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Builder for the `RetryPolicy` struct.
+///
+/// This struct provides a fluent interface for creating and configuring
+/// a `RetryPolicy` instance. It starts from the default policy.
+#[derive(Debug, Clone)]
+pub struct RetryPolicyBuilder {
+    policy: RetryPolicy,
+}
+
+impl RetryPolicyBuilder {
+    pub fn new() -> Self {
+        Self {
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.policy.max_retries = max_retries;
+        self
+    }
+
+    pub fn base_backoff(mut self, base_backoff: Duration) -> Self {
+        self.policy.base_backoff = base_backoff;
+        self
+    }
+
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.policy.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.policy.jitter = jitter;
+        self
+    }
+
+    pub fn retryable_codes(mut self, retryable_codes: &[Code]) -> Self {
+        self.policy.retryable_codes = retryable_codes.to_vec();
+        self
+    }
+
+    pub fn retry_send_signed_transaction(mut self, retry_send_signed_transaction: bool) -> Self {
+        self.policy.retry_send_signed_transaction = retry_send_signed_transaction;
+        self
+    }
+
+    pub fn build(self) -> RetryPolicy {
+        self.policy
+    }
+}
+
+impl Default for RetryPolicyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test exponential backoff
+    ///
+    /// This is used to test that the backoff doubles on each retry and is capped.
+    fn test_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy::builder()
+            .base_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(500))
+            .jitter(false)
+            .build();
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(500));
+    }
+
+    #[test]
+    /// Test jittered backoff
+    ///
+    /// This is used to test that the jitter keeps the backoff between half and the full value.
+    fn test_backoff_with_jitter_is_bounded() {
+        let policy = RetryPolicy::builder()
+            .base_backoff(Duration::from_millis(100))
+            .jitter(true)
+            .build();
+
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(100));
+            assert!(backoff <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    /// Test retryable codes
+    ///
+    /// This is used to test the default set of retryable status codes.
+    fn test_default_retryable_codes() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.is_retryable(Code::Unavailable));
+        assert!(!policy.is_retryable(Code::InvalidArgument));
+        assert!(!policy.is_retryable(Code::NotFound));
+        assert!(!policy.retry_send_signed_transaction);
+    }
+}
@@ -0,0 +1,28 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+
+/// Transaction signer
+///
+/// This trait is used to sign the transactions created by the Darklake Integrations service, so
+/// `Client::swap` can run the whole swap without leaving the SDK. Implementations usually wrap a
+/// keypair or forward the transaction to a wallet.
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    /// Signs a base64 encoded unsigned transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be decoded or signed, e.g. `Error::Signing`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the base64 encoded signed transaction.
+    async fn sign(&self, unsigned_transaction: &str) -> Result<String>;
+}
@@ -0,0 +1,445 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tracing::*;
+
+use crate::{
+    error::{Error, Result},
+    models::{CreateUnsignedTransactionResponse, SendSignedTransactionResponse, TradeStatus},
+};
+
+/// Pending trade
+///
+/// This struct is used to report a trade that is not terminal yet, either followed by a
+/// `TradeTracker` or found in the journal of a `TradeStore`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTrade {
+    /// The trade id.
+    pub trade_id: String,
+    /// The last observed status, `None` if the trade was not checked yet.
+    pub status: Option<TradeStatus>,
+    /// Whether the signed transaction is known to have been sent. A trade of the journal that
+    /// was only created may have been sent right before a crash.
+    pub submitted: bool,
+    /// When the trade was tracked, or created for a trade of the journal.
+    pub tracked_at: SystemTime,
+}
+
+/// Trade record
+///
+/// This enum is used to define an entry of the trade journal kept by a `TradeStore`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TradeRecord {
+    /// An unsigned transaction was created for the trade.
+    Created {
+        /// The trade id.
+        trade_id: String,
+        /// The order id.
+        order_id: String,
+        /// The unsigned transaction.
+        unsigned_transaction: String,
+        /// When the response was received.
+        at: SystemTime,
+    },
+    /// The signed transaction of the trade was sent.
+    Submitted {
+        /// The trade id.
+        trade_id: String,
+        /// Whether the transaction was sent successfully.
+        success: bool,
+        /// The error logs.
+        error_logs: Vec<String>,
+        /// When the response was received.
+        at: SystemTime,
+    },
+    /// The trade was given up before its signed transaction was sent, e.g. re-created because
+    /// its blockhash expired.
+    Abandoned {
+        /// The trade id.
+        trade_id: String,
+        /// When the trade was given up.
+        at: SystemTime,
+    },
+    /// A watcher observed a new status for the trade.
+    StatusChanged {
+        /// The trade id.
+        trade_id: String,
+        /// The new status.
+        status: TradeStatus,
+        /// When the status was observed.
+        at: SystemTime,
+    },
+}
+
+impl TradeRecord {
+    /// Returns the id of the trade the record is about.
+    pub fn trade_id(&self) -> &str {
+        match self {
+            TradeRecord::Created { trade_id, .. }
+            | TradeRecord::Submitted { trade_id, .. }
+            | TradeRecord::Abandoned { trade_id, .. }
+            | TradeRecord::StatusChanged { trade_id, .. } => trade_id,
+        }
+    }
+
+    pub(crate) fn created(response: &CreateUnsignedTransactionResponse) -> Self {
+        TradeRecord::Created {
+            trade_id: response.trade_id.clone(),
+            order_id: response.order_id.clone(),
+            unsigned_transaction: response.unsigned_transaction.clone(),
+            at: SystemTime::now(),
+        }
+    }
+
+    pub(crate) fn submitted(response: &SendSignedTransactionResponse) -> Self {
+        TradeRecord::Submitted {
+            trade_id: response.trade_id.clone(),
+            success: response.success,
+            error_logs: response.error_logs.clone(),
+            at: SystemTime::now(),
+        }
+    }
+
+    pub(crate) fn abandoned(trade_id: &str) -> Self {
+        TradeRecord::Abandoned {
+            trade_id: trade_id.to_string(),
+            at: SystemTime::now(),
+        }
+    }
+
+    pub(crate) fn status_changed(trade_id: &str, status: &TradeStatus) -> Self {
+        TradeRecord::StatusChanged {
+            trade_id: trade_id.to_string(),
+            status: status.clone(),
+            at: SystemTime::now(),
+        }
+    }
+}
+
+/// Trade store
+///
+/// This trait is used to persist the trade journal, so trades can be followed again after a
+/// restart with `Client::resume_pending_trades`. When a store is configured, the client appends a
+/// record for every unsigned transaction created, every trade re-created before being sent, every
+/// signed transaction sent and every status change observed by a watcher.
+#[async_trait]
+pub trait TradeStore: fmt::Debug + Send + Sync {
+    /// Appends a record to the journal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record cannot be persisted. The client logs it and carries on.
+    async fn append(&self, record: TradeRecord) -> Result<()>;
+
+    /// Returns every record of the journal, in the order they were appended.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal cannot be read.
+    async fn records(&self) -> Result<Vec<TradeRecord>>;
+
+    /// Returns the trades that were created and neither abandoned nor rejected on submission, and
+    /// did not reach a terminal status, in the order they were created.
+    ///
+    /// A trade without a successful `Submitted` record is reported with `submitted` set to
+    /// `false`: the process may have stopped between sending it and recording the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal cannot be read.
+    async fn pending_trades(&self) -> Result<Vec<PendingTrade>> {
+        Ok(pending_trades(&self.records().await?))
+    }
+}
+
+/// Replays the journal to find the trades that are not terminal yet.
+fn pending_trades(records: &[TradeRecord]) -> Vec<PendingTrade> {
+    // The trades in the order they were first seen, removed ones left as `None`.
+    let mut pending: Vec<Option<PendingTrade>> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for record in records {
+        let position = positions.get(record.trade_id()).copied();
+        match (record, position) {
+            (TradeRecord::Created { trade_id, at, .. }, None) => {
+                positions.insert(trade_id, pending.len());
+                pending.push(Some(PendingTrade {
+                    trade_id: trade_id.clone(),
+                    status: None,
+                    submitted: false,
+                    tracked_at: *at,
+                }));
+            }
+            (
+                TradeRecord::Submitted {
+                    trade_id,
+                    success: true,
+                    at,
+                    ..
+                },
+                None,
+            ) => {
+                positions.insert(trade_id, pending.len());
+                pending.push(Some(PendingTrade {
+                    trade_id: trade_id.clone(),
+                    status: None,
+                    submitted: true,
+                    tracked_at: *at,
+                }));
+            }
+            (TradeRecord::Submitted { success, .. }, Some(position)) => {
+                if *success {
+                    if let Some(trade) = &mut pending[position] {
+                        trade.submitted = true;
+                    }
+                } else {
+                    positions.remove(record.trade_id());
+                    pending[position] = None;
+                }
+            }
+            (TradeRecord::Abandoned { .. }, Some(position)) => {
+                positions.remove(record.trade_id());
+                pending[position] = None;
+            }
+            (TradeRecord::StatusChanged { status, .. }, Some(position)) => {
+                if status.is_terminal() {
+                    positions.remove(record.trade_id());
+                    pending[position] = None;
+                } else if let Some(trade) = &mut pending[position] {
+                    trade.status = Some(status.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    pending.into_iter().flatten().collect()
+}
+
+/// In-memory trade store
+///
+/// This struct is used to keep the trade journal in memory. It does not survive a restart, but
+/// is useful for tests and to query the pending trades of a running process.
+#[derive(Debug, Default)]
+pub struct InMemoryTradeStore {
+    records: Mutex<Vec<TradeRecord>>,
+}
+
+impl InMemoryTradeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TradeStore for InMemoryTradeStore {
+    async fn append(&self, record: TradeRecord) -> Result<()> {
+        self.records.lock().unwrap().push(record);
+        Ok(())
+    }
+
+    async fn records(&self) -> Result<Vec<TradeRecord>> {
+        Ok(self.records.lock().unwrap().clone())
+    }
+}
+
+/// JSON lines trade store
+///
+/// This struct is used to keep the trade journal in a file, one JSON record per line.
+/// Records are only appended, so the file grows with the number of trades. A truncated last
+/// line, left by a crash during a write, is skipped when the journal is read.
+#[derive(Debug)]
+pub struct JsonLinesTradeStore {
+    path: PathBuf,
+    file: tokio::sync::Mutex<tokio::fs::File>,
+}
+
+impl JsonLinesTradeStore {
+    /// Opens the journal at the given path, creating the file if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(|e| Error::Store(format!("Failed to open {}: {}", path.display(), e)))?;
+        // Terminate a truncated last line so the next record starts on its own line.
+        let content = tokio::fs::read(&path)
+            .await
+            .map_err(|e| Error::Store(format!("Failed to read {}: {}", path.display(), e)))?;
+        if content.last().is_some_and(|byte| *byte != b'\n') {
+            file.write_all(b"\n")
+                .await
+                .map_err(|e| Error::Store(format!("Failed to write {}: {}", path.display(), e)))?;
+        }
+        Ok(Self {
+            path,
+            file: tokio::sync::Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl TradeStore for JsonLinesTradeStore {
+    async fn append(&self, record: TradeRecord) -> Result<()> {
+        let mut line = serde_json::to_string(&record)
+            .map_err(|e| Error::Store(format!("Failed to serialize record: {}", e)))?;
+        line.push('\n');
+        let mut file = self.file.lock().await;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| Error::Store(format!("Failed to write record: {}", e)))?;
+        file.flush()
+            .await
+            .map_err(|e| Error::Store(format!("Failed to write record: {}", e)))
+    }
+
+    async fn records(&self) -> Result<Vec<TradeRecord>> {
+        // Hold the lock so no record is read half written.
+        let _file = self.file.lock().await;
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|e| Error::Store(format!("Failed to read {}: {}", self.path.display(), e)))?;
+        let mut records = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(record) => records.push(record),
+                Err(e) => warn!(
+                    "Skipping invalid trade record on line {}: {}",
+                    number + 1,
+                    e
+                ),
+            }
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submitted(trade_id: &str, success: bool) -> TradeRecord {
+        TradeRecord::Submitted {
+            trade_id: trade_id.to_string(),
+            success,
+            error_logs: vec![],
+            at: SystemTime::now(),
+        }
+    }
+
+    #[tokio::test]
+    /// Test pending trades
+    ///
+    /// This is used to test that only the trades created or submitted successfully and neither
+    /// abandoned nor terminal are pending, and that the trades only created are not marked as
+    /// submitted.
+    async fn test_pending_trades() {
+        let store = InMemoryTradeStore::new();
+        store.append(submitted("settled", true)).await.unwrap();
+        store.append(submitted("rejected", false)).await.unwrap();
+        store.append(submitted("pending", true)).await.unwrap();
+        store
+            .append(TradeRecord::status_changed(
+                "pending",
+                &TradeStatus::Confirmed,
+            ))
+            .await
+            .unwrap();
+        store
+            .append(TradeRecord::status_changed(
+                "settled",
+                &TradeStatus::Settled,
+            ))
+            .await
+            .unwrap();
+
+        for trade_id in ["abandoned", "created"] {
+            store
+                .append(TradeRecord::Created {
+                    trade_id: trade_id.to_string(),
+                    order_id: "order".to_string(),
+                    unsigned_transaction: "tx".to_string(),
+                    at: SystemTime::now(),
+                })
+                .await
+                .unwrap();
+        }
+        store
+            .append(TradeRecord::abandoned("abandoned"))
+            .await
+            .unwrap();
+
+        let pending = store.pending_trades().await.unwrap();
+
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].trade_id, "pending");
+        assert_eq!(pending[0].status, Some(TradeStatus::Confirmed));
+        assert!(pending[0].submitted);
+        assert_eq!(pending[1].trade_id, "created");
+        assert!(!pending[1].submitted);
+    }
+
+    #[tokio::test]
+    /// Test JSON lines store
+    ///
+    /// This is used to test that the records survive reopening the file and that a truncated
+    /// line is skipped.
+    async fn test_json_lines_store_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "darklake-trades-{}.jsonl",
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let store = JsonLinesTradeStore::open(&path).await.unwrap();
+        store.append(submitted("trade", true)).await.unwrap();
+        store
+            .append(TradeRecord::status_changed("trade", &TradeStatus::Signed))
+            .await
+            .unwrap();
+        drop(store);
+        tokio::fs::write(
+            &path,
+            tokio::fs::read_to_string(&path).await.unwrap() + "{\"kind\":\"sub",
+        )
+        .await
+        .unwrap();
+
+        let records = JsonLinesTradeStore::open(&path)
+            .await
+            .unwrap()
+            .records()
+            .await
+            .unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            pending_trades(&records)[0].status,
+            Some(TradeStatus::Signed)
+        );
+    }
+}
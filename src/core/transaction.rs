@@ -0,0 +1,301 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use async_trait::async_trait;
+use base64::{Engine, prelude::BASE64_STANDARD};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::{
+    core::{
+        describe::{TransactionDescription, describe_transaction},
+        signer::TransactionSigner,
+        verify::VerificationPolicy,
+    },
+    error::{Error, Result, TransactionError},
+    models::{
+        AddLiquidityResponse, CreateUnsignedTransactionResponse, InitPoolResponse,
+        RemoveLiquidityResponse,
+    },
+};
+
+/// The compute budget program.
+pub(crate) const COMPUTE_BUDGET_PROGRAM: Pubkey =
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+/// The associated token account program.
+pub(crate) const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// The system program.
+pub(crate) const SYSTEM_PROGRAM: Pubkey =
+    Pubkey::from_str_const("11111111111111111111111111111111");
+
+/// The token program.
+pub(crate) const TOKEN_PROGRAM: Pubkey =
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// The token 2022 program.
+pub(crate) const TOKEN_2022_PROGRAM: Pubkey =
+    Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Decodes a base64 encoded transaction, as returned by the Darklake Integrations service.
+///
+/// # Errors
+///
+/// Returns `Error::Transaction` if the value is not base64 or not a bincode encoded
+/// `VersionedTransaction`.
+pub fn decode_transaction(base64: &str) -> Result<VersionedTransaction> {
+    let bytes = BASE64_STANDARD
+        .decode(base64)
+        .map_err(|e| Error::Transaction(TransactionError::Base64(e.to_string())))?;
+    bincode::deserialize(&bytes)
+        .map_err(|e| Error::Transaction(TransactionError::Decode(e.to_string())))
+}
+
+/// Encodes a transaction in base64, as expected by `send_signed_transaction`.
+///
+/// # Errors
+///
+/// Returns `Error::Transaction` if the transaction cannot be serialized.
+pub fn encode_transaction(transaction: &VersionedTransaction) -> Result<String> {
+    let bytes = bincode::serialize(transaction)
+        .map_err(|e| Error::Transaction(TransactionError::Encode(e.to_string())))?;
+    Ok(BASE64_STANDARD.encode(bytes))
+}
+
+/// Signs a transaction with one or more signers.
+///
+/// Each signature is placed in the slot of the signer among the required signers of the message.
+/// The slots of the other required signers are kept, so a transaction can be signed in several
+/// steps.
+///
+/// # Errors
+///
+/// Returns `Error::Transaction` if a signer is not a required signer of the transaction, and
+/// `Error::Signing` if a signer fails.
+pub fn sign_transaction(
+    mut transaction: VersionedTransaction,
+    signers: &[&dyn Signer],
+) -> Result<VersionedTransaction> {
+    let required = usize::from(transaction.message.header().num_required_signatures);
+    let required_keys = &transaction.message.static_account_keys()
+        [..required.min(transaction.message.static_account_keys().len())];
+    let mut slots = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let pubkey = signer
+            .try_pubkey()
+            .map_err(|e| Error::Signing(e.to_string()))?;
+        let slot = required_keys
+            .iter()
+            .position(|key| *key == pubkey)
+            .ok_or_else(|| {
+                Error::Transaction(TransactionError::UnexpectedSigner(pubkey.to_string()))
+            })?;
+        slots.push(slot);
+    }
+    let message = transaction.message.serialize();
+    transaction.signatures.resize(required, Default::default());
+    for (signer, slot) in signers.iter().zip(slots) {
+        transaction.signatures[slot] = signer
+            .try_sign_message(&message)
+            .map_err(|e| Error::Signing(e.to_string()))?;
+    }
+    Ok(transaction)
+}
+
+/// Unsigned transaction
+///
+/// This trait is implemented by the responses holding a base64 encoded unsigned transaction,
+/// to decode and sign it without leaving the SDK.
+pub trait UnsignedTransaction {
+    /// Returns the base64 encoded unsigned transaction.
+    fn encoded_transaction(&self) -> &str;
+
+    /// Decodes the unsigned transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Transaction` if the transaction cannot be decoded.
+    fn decode_transaction(&self) -> Result<VersionedTransaction> {
+        decode_transaction(self.encoded_transaction())
+    }
+
+    /// Signs the unsigned transaction with one or more signers, see `sign_transaction`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be decoded, signed or encoded.
+    ///
+    /// # Returns
+    ///
+    /// Returns the base64 encoded signed transaction, to pass to `send_signed_transaction`.
+    fn sign_transaction(&self, signers: &[&dyn Signer]) -> Result<String> {
+        encode_transaction(&sign_transaction(self.decode_transaction()?, signers)?)
+    }
+
+    /// Returns the recent blockhash of the unsigned transaction, in base58. The transaction can
+    /// no longer be sent once the blockhash expires.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Transaction` if the transaction cannot be decoded.
+    fn recent_blockhash(&self) -> Result<String> {
+        Ok(self
+            .decode_transaction()?
+            .message
+            .recent_blockhash()
+            .to_string())
+    }
+
+    /// Describes the unsigned transaction, to review it before signing, see
+    /// `describe_transaction`. The instructions of unknown programs are shown as raw data.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Transaction` if the transaction cannot be decoded.
+    fn describe(&self) -> Result<TransactionDescription> {
+        Ok(describe_transaction(&self.decode_transaction()?, None))
+    }
+
+    /// Describes the unsigned transaction, decoding the instructions of the Darklake programs of
+    /// the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Transaction` if the transaction cannot be decoded.
+    fn describe_with(&self, policy: &VerificationPolicy) -> Result<TransactionDescription> {
+        Ok(describe_transaction(
+            &self.decode_transaction()?,
+            Some(policy),
+        ))
+    }
+}
+
+impl UnsignedTransaction for CreateUnsignedTransactionResponse {
+    fn encoded_transaction(&self) -> &str {
+        &self.unsigned_transaction
+    }
+}
+
+impl UnsignedTransaction for InitPoolResponse {
+    fn encoded_transaction(&self) -> &str {
+        &self.unsigned_transaction
+    }
+}
+
+impl UnsignedTransaction for AddLiquidityResponse {
+    fn encoded_transaction(&self) -> &str {
+        &self.unsigned_transaction
+    }
+}
+
+impl UnsignedTransaction for RemoveLiquidityResponse {
+    fn encoded_transaction(&self) -> &str {
+        &self.unsigned_transaction
+    }
+}
+
+/// Solana signer
+///
+/// This struct is used to sign the transactions of `Client::swap` with local signers, e.g. a
+/// `Keypair`.
+pub struct SolanaSigner<S> {
+    signers: Vec<S>,
+}
+
+impl<S: Signer + Send + Sync> SolanaSigner<S> {
+    pub fn new(signer: S) -> Self {
+        Self {
+            signers: vec![signer],
+        }
+    }
+
+    /// Creates a signer signing with every given signer, e.g. the fee payer and the user.
+    pub fn with_signers(signers: Vec<S>) -> Self {
+        Self { signers }
+    }
+}
+
+#[async_trait]
+impl<S: Signer + Send + Sync> TransactionSigner for SolanaSigner<S> {
+    async fn sign(&self, unsigned_transaction: &str) -> Result<String> {
+        let signers: Vec<&dyn Signer> = self.signers.iter().map(|s| s as &dyn Signer).collect();
+        let transaction = sign_transaction(decode_transaction(unsigned_transaction)?, &signers)?;
+        encode_transaction(&transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        message::{Message, VersionedMessage},
+        signature::Keypair,
+    };
+
+    use std::time::Instant;
+
+    use super::*;
+
+    /// Returns a transaction with two required signers, the payer first.
+    fn unsigned(payer: &Keypair, user: &Keypair) -> String {
+        let instruction = Instruction::new_with_bytes(
+            solana_sdk::pubkey::Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(user.pubkey(), true)],
+        );
+        let message = Message::new(&[instruction], Some(&payer.pubkey()));
+        encode_transaction(&VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::Legacy(message),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    /// Test transaction signing
+    ///
+    /// This is used to test that each signer signs its own slot, that partial signatures are
+    /// kept and that an unexpected signer is rejected.
+    fn test_sign_transaction() {
+        let payer = Keypair::new();
+        let user = Keypair::new();
+        let response = CreateUnsignedTransactionResponse {
+            unsigned_transaction: unsigned(&payer, &user),
+            order_id: "order".to_string(),
+            trade_id: "trade".to_string(),
+            received_at: Instant::now(),
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            priority_level: None,
+        };
+
+        let partial = response.sign_transaction(&[&user]).unwrap();
+        let signed = decode_transaction(&partial).unwrap();
+        assert_eq!(signed.signatures.len(), 2);
+        assert_eq!(signed.signatures[0], Default::default());
+        let signed = decode_transaction(
+            &encode_transaction(&sign_transaction(signed, &[&payer]).unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert!(signed.verify_with_results().iter().all(|valid| *valid));
+
+        assert!(matches!(
+            response.sign_transaction(&[&Keypair::new()]),
+            Err(Error::Transaction(TransactionError::UnexpectedSigner(_)))
+        ));
+        assert!(matches!(
+            decode_transaction("not base64!"),
+            Err(Error::Transaction(TransactionError::Base64(_)))
+        ));
+        assert!(matches!(
+            decode_transaction("AAAA"),
+            Err(Error::Transaction(TransactionError::Decode(_)))
+        ));
+    }
+}
@@ -0,0 +1,800 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use solana_pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::{
+    core::transaction::{
+        ASSOCIATED_TOKEN_PROGRAM, COMPUTE_BUDGET_PROGRAM, SYSTEM_PROGRAM, TOKEN_2022_PROGRAM,
+        TOKEN_PROGRAM, decode_transaction,
+    },
+    error::{Error, Result},
+    models::{
+        AddLiquidityRequest, AddLiquidityResponse, CreateUnsignedTransactionRequest,
+        CreateUnsignedTransactionResponse, InitPoolRequest, InitPoolResponse, Mint,
+        RemoveLiquidityRequest, RemoveLiquidityResponse, WalletAddress,
+    },
+};
+
+/// The programs a Darklake transaction may invoke besides the Darklake programs.
+const DEFAULT_ALLOWED_PROGRAMS: [Pubkey; 5] = [
+    COMPUTE_BUDGET_PROGRAM,
+    ASSOCIATED_TOKEN_PROGRAM,
+    SYSTEM_PROGRAM,
+    TOKEN_PROGRAM,
+    TOKEN_2022_PROGRAM,
+];
+
+const TOKEN_PROGRAMS: [Pubkey; 2] = [TOKEN_PROGRAM, TOKEN_2022_PROGRAM];
+
+/// The mint of wrapped SOL.
+const NATIVE_MINT: Pubkey = Pubkey::from_str_const("So11111111111111111111111111111111111111112");
+
+/// The instructions of the compute budget program accepted in a Darklake transaction.
+const REQUEST_HEAP_FRAME: u8 = 1;
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+const SET_LOADED_ACCOUNTS_DATA_SIZE_LIMIT: u8 = 4;
+
+/// The highest compute unit price accepted by default, in micro lamports, when the request does
+/// not set one.
+const DEFAULT_MAX_COMPUTE_UNIT_PRICE: u64 = 1_000_000;
+
+/// The highest compute unit limit accepted by default when the request does not set one, the
+/// limit of a transaction.
+const DEFAULT_MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// The `Transfer` instruction of the system program.
+const SYSTEM_TRANSFER: u32 = 2;
+
+/// The instructions of the token programs accepted in a Darklake transaction.
+const INITIALIZE_ACCOUNT: u8 = 1;
+const CLOSE_ACCOUNT: u8 = 9;
+const INITIALIZE_ACCOUNT_2: u8 = 16;
+const SYNC_NATIVE: u8 = 17;
+const INITIALIZE_ACCOUNT_3: u8 = 18;
+
+/// Verification policy
+///
+/// This struct is used to define what an unsigned transaction built by the Darklake Integrations
+/// service may contain. The Darklake program ids are not built into the SDK, they must be set to
+/// the ones published for the network the client connects to.
+///
+/// Besides the Darklake programs, only the compute budget, associated token account, system and
+/// token programs are allowed by default. Since the Darklake programs move the funds themselves,
+/// only the instructions wrapping and unwrapping SOL for the user are accepted from the system and
+/// token programs, even when more programs are allowed: a system transfer from the user to their
+/// wrapped SOL associated token account when one of the mints is wrapped SOL, and the
+/// initialization, sync and closing of token accounts owned by the user.
+///
+/// The compute unit price and limit set by the transaction must be the ones of the request when
+/// it sets them, and at most the maximums of the policy otherwise, 1,000,000 micro lamports and
+/// 1,400,000 units by default.
+#[derive(Debug, Clone)]
+pub struct VerificationPolicy {
+    darklake_programs: Vec<Pubkey>,
+    allowed_programs: Vec<Pubkey>,
+    instruction_names: Vec<([u8; 8], String)>,
+    max_compute_unit_price: u64,
+    max_compute_unit_limit: u32,
+}
+
+impl VerificationPolicy {
+    pub fn builder() -> VerificationPolicyBuilder {
+        VerificationPolicyBuilder::new()
+    }
+
+    fn is_allowed(&self, program: &Pubkey) -> bool {
+        self.is_darklake(program) || self.allowed_programs.contains(program)
+    }
+
+    /// Whether the program is one of the Darklake programs.
+    pub(crate) fn is_darklake(&self, program: &Pubkey) -> bool {
+        self.darklake_programs.contains(program)
+    }
+
+    /// Returns the name of the Darklake instruction starting with the given data, if known.
+    pub(crate) fn instruction_name(&self, data: &[u8]) -> Option<&str> {
+        self.instruction_names
+            .iter()
+            .find(|(discriminator, _)| data.starts_with(discriminator))
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+/// VerificationPolicyBuilder
+///
+/// This struct is used to build a VerificationPolicy.
+#[derive(Debug, Clone)]
+pub struct VerificationPolicyBuilder {
+    darklake_programs: Vec<Pubkey>,
+    allowed_programs: Vec<Pubkey>,
+    instruction_names: Vec<([u8; 8], String)>,
+    max_compute_unit_price: u64,
+    max_compute_unit_limit: u32,
+}
+
+impl Default for VerificationPolicyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VerificationPolicyBuilder {
+    pub fn new() -> Self {
+        Self {
+            darklake_programs: Vec::new(),
+            allowed_programs: DEFAULT_ALLOWED_PROGRAMS.to_vec(),
+            instruction_names: Vec::new(),
+            max_compute_unit_price: DEFAULT_MAX_COMPUTE_UNIT_PRICE,
+            max_compute_unit_limit: DEFAULT_MAX_COMPUTE_UNIT_LIMIT,
+        }
+    }
+
+    /// Adds a Darklake program, which must hold the instruction of the operation.
+    pub fn darklake_program(mut self, program: Pubkey) -> Self {
+        self.darklake_programs.push(program);
+        self
+    }
+
+    /// Allows another program to be invoked, e.g. a memo program.
+    pub fn allow_program(mut self, program: Pubkey) -> Self {
+        self.allowed_programs.push(program);
+        self
+    }
+
+    /// Names the Darklake instructions starting with the given 8 bytes discriminator, for
+    /// `UnsignedTransaction::describe_with`.
+    pub fn instruction_name(mut self, discriminator: [u8; 8], name: &str) -> Self {
+        self.instruction_names
+            .push((discriminator, name.to_string()));
+        self
+    }
+
+    /// Sets the highest compute unit price accepted, in micro lamports, when the request does not
+    /// set one, e.g. when the service resolves a priority level.
+    pub fn max_compute_unit_price(mut self, max_compute_unit_price: u64) -> Self {
+        self.max_compute_unit_price = max_compute_unit_price;
+        self
+    }
+
+    /// Sets the highest compute unit limit accepted when the request does not set one.
+    pub fn max_compute_unit_limit(mut self, max_compute_unit_limit: u32) -> Self {
+        self.max_compute_unit_limit = max_compute_unit_limit;
+        self
+    }
+
+    /// Builds the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if no Darklake program is set.
+    pub fn build(self) -> Result<VerificationPolicy> {
+        if self.darklake_programs.is_empty() {
+            return Err(Error::InvalidConfig(
+                "At least one Darklake program is required".to_string(),
+            ));
+        }
+        Ok(VerificationPolicy {
+            darklake_programs: self.darklake_programs,
+            allowed_programs: self.allowed_programs,
+            instruction_names: self.instruction_names,
+            max_compute_unit_price: self.max_compute_unit_price,
+            max_compute_unit_limit: self.max_compute_unit_limit,
+        })
+    }
+}
+
+/// What the transaction of a request must contain.
+struct Expected {
+    payer: WalletAddress,
+    mints: [Mint; 2],
+    amounts: Vec<u64>,
+    compute_unit_price: Option<u64>,
+    compute_unit_limit: Option<u32>,
+}
+
+/// Verifies the unsigned transaction of a swap before it is signed.
+///
+/// The fee payer must be the user, only allowed programs may be invoked, the system and token
+/// programs may only wrap and unwrap SOL for the user, the compute budget must match the request
+/// or stay within the policy, and a Darklake instruction must reference both mints and encode
+/// `amount_in` and `min_out`.
+///
+/// # Errors
+///
+/// Returns `Error::Verification` if the transaction does not match the request, and
+/// `Error::Transaction` if it cannot be decoded. The transaction must not be signed then.
+///
+/// # Returns
+///
+/// Returns the decoded transaction, to sign with `sign_transaction`.
+pub fn verify_unsigned_swap(
+    request: &CreateUnsignedTransactionRequest,
+    response: &CreateUnsignedTransactionResponse,
+    policy: &VerificationPolicy,
+) -> Result<VersionedTransaction> {
+    verify(
+        &response.unsigned_transaction,
+        Expected {
+            payer: request.user_address,
+            mints: [request.token_mint_x, request.token_mint_y],
+            amounts: vec![request.amount_in, request.min_out],
+            compute_unit_price: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+        },
+        policy,
+    )
+}
+
+/// Verifies the unsigned transaction of a pool initialization before it is signed, like
+/// `verify_unsigned_swap`, with `amount_x` and `amount_y` as the amounts.
+///
+/// # Errors
+///
+/// Returns `Error::Verification` if the transaction does not match the request, and
+/// `Error::Transaction` if it cannot be decoded.
+pub fn verify_unsigned_init_pool(
+    request: &InitPoolRequest,
+    response: &InitPoolResponse,
+    policy: &VerificationPolicy,
+) -> Result<VersionedTransaction> {
+    verify(
+        &response.unsigned_transaction,
+        Expected {
+            payer: request.user_address,
+            mints: [request.token_mint_x, request.token_mint_y],
+            amounts: vec![request.amount_x, request.amount_y],
+            compute_unit_price: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+        },
+        policy,
+    )
+}
+
+/// Verifies the unsigned transaction of a liquidity deposit before it is signed, like
+/// `verify_unsigned_swap`, with `amount_lp`, `max_amount_x` and `max_amount_y` as the amounts.
+///
+/// # Errors
+///
+/// Returns `Error::Verification` if the transaction does not match the request, and
+/// `Error::Transaction` if it cannot be decoded.
+pub fn verify_unsigned_add_liquidity(
+    request: &AddLiquidityRequest,
+    response: &AddLiquidityResponse,
+    policy: &VerificationPolicy,
+) -> Result<VersionedTransaction> {
+    verify(
+        &response.unsigned_transaction,
+        Expected {
+            payer: request.user_address,
+            mints: [request.token_mint_x, request.token_mint_y],
+            amounts: vec![
+                request.amount_lp,
+                request.max_amount_x,
+                request.max_amount_y,
+            ],
+            compute_unit_price: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+        },
+        policy,
+    )
+}
+
+/// Verifies the unsigned transaction of a liquidity withdrawal before it is signed, like
+/// `verify_unsigned_swap`, with `amount_lp`, `min_amount_x` and `min_amount_y` as the amounts.
+///
+/// # Errors
+///
+/// Returns `Error::Verification` if the transaction does not match the request, and
+/// `Error::Transaction` if it cannot be decoded.
+pub fn verify_unsigned_remove_liquidity(
+    request: &RemoveLiquidityRequest,
+    response: &RemoveLiquidityResponse,
+    policy: &VerificationPolicy,
+) -> Result<VersionedTransaction> {
+    verify(
+        &response.unsigned_transaction,
+        Expected {
+            payer: request.user_address,
+            mints: [request.token_mint_x, request.token_mint_y],
+            amounts: vec![
+                request.amount_lp,
+                request.min_amount_x,
+                request.min_amount_y,
+            ],
+            compute_unit_price: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+        },
+        policy,
+    )
+}
+
+/// Decodes the transaction and checks it against the expected content.
+fn verify(
+    base64: &str,
+    expected: Expected,
+    policy: &VerificationPolicy,
+) -> Result<VersionedTransaction> {
+    let transaction = decode_transaction(base64)?;
+    let message = &transaction.message;
+    // Accounts loaded from address lookup tables cannot be resolved offline, so only the static
+    // keys are checked. The programs are always static keys.
+    let keys = message.static_account_keys();
+    let refuse = |reason: String| Err(Error::Verification(reason));
+
+    let payer = Pubkey::from(expected.payer);
+    if keys.first() != Some(&payer) {
+        return refuse(format!("the fee payer is not the user {}", payer));
+    }
+
+    let mints = expected.mints.map(Pubkey::from);
+    // The account SOL may be wrapped into, when the user swaps SOL.
+    let native_account = mints.contains(&NATIVE_MINT).then(|| {
+        Pubkey::find_program_address(
+            &[payer.as_ref(), TOKEN_PROGRAM.as_ref(), NATIVE_MINT.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM,
+        )
+        .0
+    });
+    let mut operation_found = false;
+    for (index, instruction) in message.instructions().iter().enumerate() {
+        let Some(program) = keys.get(usize::from(instruction.program_id_index)) else {
+            return refuse(format!("instruction {} has no program", index));
+        };
+        if !policy.is_allowed(program) {
+            return refuse(format!(
+                "instruction {} invokes {}, which is not allowed",
+                index, program
+            ));
+        }
+        if *program == COMPUTE_BUDGET_PROGRAM {
+            if let Some(reason) = compute_budget_refusal(&instruction.data, &expected, policy) {
+                return refuse(format!("instruction {} is {}", index, reason));
+            }
+            continue;
+        }
+        let accounts: Vec<Option<&Pubkey>> = instruction
+            .accounts
+            .iter()
+            .map(|account| keys.get(usize::from(*account)))
+            .collect();
+        if let Some(reason) = refusal(
+            program,
+            &instruction.data,
+            &accounts,
+            &payer,
+            native_account.as_ref(),
+        ) {
+            return refuse(format!("instruction {} is {}", index, reason));
+        }
+        if policy.is_darklake(program) {
+            let has_mints = mints.iter().all(|mint| accounts.contains(&Some(mint)));
+            let has_amounts = expected.amounts.iter().all(|amount| {
+                instruction
+                    .data
+                    .windows(8)
+                    .any(|window| window == amount.to_le_bytes())
+            });
+            operation_found |= has_mints && has_amounts;
+        }
+    }
+    if !operation_found {
+        return refuse(format!(
+            "no Darklake instruction references the mints {} and {} with the amounts {:?}",
+            mints[0], mints[1], expected.amounts
+        ));
+    }
+    Ok(transaction)
+}
+
+/// Returns why an instruction of the compute budget program is refused, if it is.
+///
+/// The compute unit price and limit must be the ones of the request if it sets them, and at most
+/// the maximums of the policy otherwise. The heap frame and loaded accounts data size requests are
+/// accepted, any other instruction is refused.
+fn compute_budget_refusal(
+    data: &[u8],
+    expected: &Expected,
+    policy: &VerificationPolicy,
+) -> Option<String> {
+    match data.first().copied() {
+        Some(SET_COMPUTE_UNIT_PRICE) => {
+            let Some(price) = data
+                .get(1..9)
+                .and_then(|price| price.try_into().ok())
+                .map(u64::from_le_bytes)
+            else {
+                return Some("a malformed compute unit price".to_string());
+            };
+            match expected.compute_unit_price {
+                Some(requested) if price != requested => Some(format!(
+                    "a compute unit price of {} micro lamports instead of the {} requested",
+                    price, requested
+                )),
+                None if price > policy.max_compute_unit_price => Some(format!(
+                    "a compute unit price of {} micro lamports, above the {} allowed",
+                    price, policy.max_compute_unit_price
+                )),
+                _ => None,
+            }
+        }
+        Some(SET_COMPUTE_UNIT_LIMIT) => {
+            let Some(limit) = data
+                .get(1..5)
+                .and_then(|limit| limit.try_into().ok())
+                .map(u32::from_le_bytes)
+            else {
+                return Some("a malformed compute unit limit".to_string());
+            };
+            match expected.compute_unit_limit {
+                Some(requested) if limit != requested => Some(format!(
+                    "a compute unit limit of {} instead of the {} requested",
+                    limit, requested
+                )),
+                None if limit > policy.max_compute_unit_limit => Some(format!(
+                    "a compute unit limit of {}, above the {} allowed",
+                    limit, policy.max_compute_unit_limit
+                )),
+                _ => None,
+            }
+        }
+        Some(REQUEST_HEAP_FRAME | SET_LOADED_ACCOUNTS_DATA_SIZE_LIMIT) => None,
+        _ => Some("an unknown compute budget instruction".to_string()),
+    }
+}
+
+/// Returns why an instruction of the system or token programs is refused, if it is.
+///
+/// A system transfer must go from the user to their wrapped SOL account, a token account must be
+/// initialized for the user and closed to the user. Every other instruction of these programs,
+/// e.g. a transfer, an approval or an authority change, is refused.
+fn refusal(
+    program: &Pubkey,
+    data: &[u8],
+    accounts: &[Option<&Pubkey>],
+    payer: &Pubkey,
+    native_account: Option<&Pubkey>,
+) -> Option<&'static str> {
+    let account = |index: usize| accounts.get(index).copied().flatten();
+    if *program == SYSTEM_PROGRAM {
+        let tag = data
+            .get(..4)
+            .and_then(|tag| tag.try_into().ok())
+            .map(u32::from_le_bytes);
+        if tag != Some(SYSTEM_TRANSFER) {
+            Some("a system instruction other than a transfer")
+        } else if account(0) != Some(payer)
+            || native_account.is_none()
+            || account(1) != native_account
+        {
+            Some("a transfer other than wrapping SOL for the user")
+        } else {
+            None
+        }
+    } else if TOKEN_PROGRAMS.contains(program) {
+        match data.first().copied() {
+            Some(INITIALIZE_ACCOUNT) => (account(2) != Some(payer))
+                .then_some("a token account initialized for another owner"),
+            Some(INITIALIZE_ACCOUNT_2 | INITIALIZE_ACCOUNT_3) => (data.get(1..33)
+                != Some(payer.as_ref()))
+            .then_some("a token account initialized for another owner"),
+            Some(SYNC_NATIVE) => None,
+            Some(CLOSE_ACCOUNT) => {
+                (account(1) != Some(payer)).then_some("a token account closed to another account")
+            }
+            _ => Some("a token instruction other than initializing, syncing or closing an account"),
+        }
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        message::{Message, VersionedMessage},
+        signature::Keypair,
+        signer::Signer,
+    };
+
+    use std::time::Instant;
+
+    use super::*;
+    use crate::core::transaction::encode_transaction;
+
+    const DARKLAKE: Pubkey = Pubkey::from_str_const("DarkLake11111111111111111111111111111111111");
+
+    /// Returns the swap request of the user and the response holding the given instructions.
+    fn swap(
+        user: &Keypair,
+        instructions: &[Instruction],
+    ) -> (
+        CreateUnsignedTransactionRequest,
+        CreateUnsignedTransactionResponse,
+    ) {
+        let request = CreateUnsignedTransactionRequest::builder(
+            user.pubkey().into(),
+            "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX"
+                .parse()
+                .unwrap(),
+            "So11111111111111111111111111111111111111112"
+                .parse()
+                .unwrap(),
+            1_000,
+            900,
+        )
+        .build()
+        .unwrap();
+        let message = Message::new(instructions, Some(&user.pubkey()));
+        let response = CreateUnsignedTransactionResponse {
+            unsigned_transaction: encode_transaction(&VersionedTransaction {
+                signatures: vec![],
+                message: VersionedMessage::Legacy(message),
+            })
+            .unwrap(),
+            order_id: "order".to_string(),
+            trade_id: "trade".to_string(),
+            received_at: Instant::now(),
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            priority_level: None,
+        };
+        (request, response)
+    }
+
+    /// Returns a Darklake swap instruction with the given minimum output.
+    fn swap_instruction(user: &Keypair, min_out: u64) -> Instruction {
+        let mut data = vec![0xd1; 8];
+        data.extend(1_000u64.to_le_bytes());
+        data.extend(min_out.to_le_bytes());
+        Instruction::new_with_bytes(
+            DARKLAKE,
+            &data,
+            vec![
+                AccountMeta::new(user.pubkey(), true),
+                AccountMeta::new_readonly(
+                    Pubkey::from_str_const("DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX"),
+                    false,
+                ),
+                AccountMeta::new_readonly(
+                    Pubkey::from_str_const("So11111111111111111111111111111111111111112"),
+                    false,
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    /// Test swap verification
+    ///
+    /// This is used to test that a matching transaction is accepted and that a wrong payer, an
+    /// unknown program, a transfer or a wrong amount is refused.
+    fn test_verify_unsigned_swap() {
+        let user = Keypair::new();
+        let policy = VerificationPolicy::builder()
+            .darklake_program(DARKLAKE)
+            .allow_program(SYSTEM_PROGRAM)
+            .build()
+            .unwrap();
+        let verify = |instructions: &[Instruction]| {
+            let (request, response) = swap(&user, instructions);
+            verify_unsigned_swap(&request, &response, &policy)
+        };
+
+        assert!(verify(&[swap_instruction(&user, 900)]).is_ok());
+
+        let refused = [
+            vec![swap_instruction(&user, 800)],
+            vec![
+                swap_instruction(&user, 900),
+                Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]),
+            ],
+            vec![
+                swap_instruction(&user, 900),
+                Instruction::new_with_bytes(
+                    SYSTEM_PROGRAM,
+                    &[2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0],
+                    vec![
+                        AccountMeta::new(user.pubkey(), true),
+                        AccountMeta::new(Pubkey::new_unique(), false),
+                    ],
+                ),
+            ],
+        ];
+        for instructions in refused {
+            assert!(matches!(verify(&instructions), Err(Error::Verification(_))));
+        }
+
+        let (request, response) = swap(&Keypair::new(), &[swap_instruction(&user, 900)]);
+        let request = CreateUnsignedTransactionRequest {
+            user_address: user.pubkey().into(),
+            ..request
+        };
+        assert!(matches!(
+            verify_unsigned_swap(&request, &response, &policy),
+            Err(Error::Verification(_))
+        ));
+    }
+
+    /// Returns a compute budget instruction with the given tag and little endian value.
+    fn compute_budget_instruction(tag: u8, value: &[u8]) -> Instruction {
+        let mut data = vec![tag];
+        data.extend(value);
+        Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM, &data, vec![])
+    }
+
+    #[test]
+    /// Test compute budget verification
+    ///
+    /// This is used to test that an inflated compute unit price or limit is refused, whether the
+    /// request sets it or the policy caps it, and that the cap is configurable.
+    fn test_verify_compute_budget() {
+        let user = Keypair::new();
+        let policy = VerificationPolicy::builder()
+            .darklake_program(DARKLAKE)
+            .build()
+            .unwrap();
+        let with_budget = |price: u64, limit: u32| {
+            swap(
+                &user,
+                &[
+                    compute_budget_instruction(SET_COMPUTE_UNIT_LIMIT, &limit.to_le_bytes()),
+                    compute_budget_instruction(SET_COMPUTE_UNIT_PRICE, &price.to_le_bytes()),
+                    swap_instruction(&user, 900),
+                ],
+            )
+        };
+
+        let (request, response) = with_budget(10_000, 200_000);
+        assert!(verify_unsigned_swap(&request, &response, &policy).is_ok());
+
+        for (price, limit) in [(50_000_000, 200_000), (10_000, 1_400_001)] {
+            let (request, response) = with_budget(price, limit);
+            assert!(matches!(
+                verify_unsigned_swap(&request, &response, &policy),
+                Err(Error::Verification(_))
+            ));
+        }
+
+        let (request, response) = with_budget(50_000, 200_000);
+        let request = CreateUnsignedTransactionRequest {
+            priority_fee_micro_lamports: Some(10_000),
+            ..request
+        };
+        assert!(matches!(
+            verify_unsigned_swap(&request, &response, &policy),
+            Err(Error::Verification(_))
+        ));
+
+        let (request, response) = with_budget(50_000_000, 200_000);
+        let policy = VerificationPolicy::builder()
+            .darklake_program(DARKLAKE)
+            .max_compute_unit_price(100_000_000)
+            .build()
+            .unwrap();
+        assert!(verify_unsigned_swap(&request, &response, &policy).is_ok());
+
+        let (request, response) = swap(
+            &user,
+            &[
+                compute_budget_instruction(9, &[]),
+                swap_instruction(&user, 900),
+            ],
+        );
+        assert!(matches!(
+            verify_unsigned_swap(&request, &response, &policy),
+            Err(Error::Verification(_))
+        ));
+    }
+
+    /// Returns the wrapped SOL associated token account of the user.
+    fn native_account(user: &Keypair) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                user.pubkey().as_ref(),
+                TOKEN_PROGRAM.as_ref(),
+                NATIVE_MINT.as_ref(),
+            ],
+            &ASSOCIATED_TOKEN_PROGRAM,
+        )
+        .0
+    }
+
+    /// Returns a token program instruction with the given data and accounts.
+    fn token_instruction(data: &[u8], accounts: &[Pubkey]) -> Instruction {
+        Instruction::new_with_bytes(
+            TOKEN_PROGRAM,
+            data,
+            accounts
+                .iter()
+                .map(|account| AccountMeta::new(*account, false))
+                .collect(),
+        )
+    }
+
+    #[test]
+    /// Test SOL swap verification
+    ///
+    /// This is used to test that wrapping SOL into the wrapped SOL account of the user and
+    /// unwrapping it back to the user is accepted with the default policy.
+    fn test_verify_unsigned_sol_swap() {
+        let user = Keypair::new();
+        let policy = VerificationPolicy::builder()
+            .darklake_program(DARKLAKE)
+            .build()
+            .unwrap();
+        let native_account = native_account(&user);
+        let mut wrap = vec![2, 0, 0, 0];
+        wrap.extend(1_000u64.to_le_bytes());
+        let instructions = [
+            Instruction::new_with_bytes(
+                SYSTEM_PROGRAM,
+                &wrap,
+                vec![
+                    AccountMeta::new(user.pubkey(), true),
+                    AccountMeta::new(native_account, false),
+                ],
+            ),
+            token_instruction(&[SYNC_NATIVE], &[native_account]),
+            swap_instruction(&user, 900),
+            token_instruction(
+                &[CLOSE_ACCOUNT],
+                &[native_account, user.pubkey(), user.pubkey()],
+            ),
+        ];
+
+        let (request, response) = swap(&user, &instructions);
+        assert!(verify_unsigned_swap(&request, &response, &policy).is_ok());
+    }
+
+    #[test]
+    /// Test token instructions verification
+    ///
+    /// This is used to test that approvals, authority changes, transfers and closing an account
+    /// to someone else are refused, even though the token program is allowed.
+    fn test_verify_refuses_token_instructions() {
+        let user = Keypair::new();
+        let policy = VerificationPolicy::builder()
+            .darklake_program(DARKLAKE)
+            .build()
+            .unwrap();
+        let account = native_account(&user);
+        let attacker = Pubkey::new_unique();
+        let mut approve = vec![4];
+        approve.extend(u64::MAX.to_le_bytes());
+        let mut set_authority = vec![6, 2, 1];
+        set_authority.extend(attacker.to_bytes());
+        let mut transfer = vec![3];
+        transfer.extend(1_000u64.to_le_bytes());
+        let mut wrap_to_attacker = vec![2, 0, 0, 0];
+        wrap_to_attacker.extend(1_000u64.to_le_bytes());
+
+        let refused = [
+            token_instruction(&approve, &[account, attacker, user.pubkey()]),
+            token_instruction(&set_authority, &[account, user.pubkey()]),
+            token_instruction(&transfer, &[account, attacker, user.pubkey()]),
+            token_instruction(&[CLOSE_ACCOUNT], &[account, attacker, user.pubkey()]),
+            Instruction::new_with_bytes(
+                SYSTEM_PROGRAM,
+                &wrap_to_attacker,
+                vec![
+                    AccountMeta::new(user.pubkey(), true),
+                    AccountMeta::new(attacker, false),
+                ],
+            ),
+        ];
+        for instruction in refused {
+            let (request, response) = swap(&user, &[swap_instruction(&user, 900), instruction]);
+            assert!(matches!(
+                verify_unsigned_swap(&request, &response, &policy),
+                Err(Error::Verification(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,381 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::{fmt, time::Duration};
+
+use tonic::{Code, metadata::MetadataMap};
+
+use crate::{core::retry::RetryPolicy, models::CheckTradeStatusResponse};
+
+/// Result type returned by the SDK.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Error type for the SDK.
+///
+/// This enum represents the different errors that can occur when interacting with the
+/// Darklake Integrations service.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The configuration is invalid.
+    InvalidConfig(String),
+    /// The URI of the service is invalid.
+    InvalidUri(String),
+    /// The connection to the service could not be established.
+    ConnectionFailed(String),
+    /// The call did not complete before the client side timeout expired.
+    Timeout {
+        /// The tracking id of the request, if the request carries one.
+        tracking_id: Option<String>,
+    },
+    /// The service answered with a gRPC error status.
+    Grpc {
+        /// The gRPC status code.
+        code: Code,
+        /// The error message returned by the service.
+        message: String,
+        /// The metadata attached to the error status.
+        metadata: Box<MetadataMap>,
+        /// The tracking id of the request, if the request carries one.
+        tracking_id: Option<String>,
+    },
+    /// A channel used to report progress was closed by its receiver.
+    ChannelClosed(String),
+    /// The watcher stopped before the trade reached a terminal status.
+    WatchStopped {
+        /// Why the watcher stopped.
+        reason: StopReason,
+        /// The last status observed before the watcher stopped, if any.
+        last_response: Option<CheckTradeStatusResponse>,
+    },
+    /// The trade status stream ended before the trade reached a terminal status.
+    StreamEnded,
+    /// The trade store failed to persist or read the trade journal.
+    Store(String),
+    /// The request was rejected by the client side validation, before any call.
+    Validation(Vec<FieldError>),
+    /// An address is not a base58 encoded 32 bytes public key.
+    InvalidAddress(String),
+    /// An amount cannot be parsed or does not match the token it is paired with.
+    InvalidAmount(String),
+    /// The transaction signer failed to sign a transaction.
+    Signing(String),
+    /// A transaction cannot be decoded, signed or encoded.
+    Transaction(TransactionError),
+    /// An unsigned transaction does not match the request it was built for, and must not be signed.
+    Verification(String),
+    /// The quote used to build a request is older than the allowed age.
+    StaleQuote {
+        /// The age of the quote.
+        age: Duration,
+        /// The maximum allowed age.
+        max_age: Duration,
+    },
+    /// The blockhash of the transaction expired before it was sent, after the trade was re-created
+    /// `refreshes` times.
+    BlockhashExpired {
+        /// The number of times the trade was re-created.
+        refreshes: u32,
+    },
+    /// The service could not send the signed transaction, for another reason than an expired
+    /// blockhash.
+    SendFailed {
+        /// The trade id.
+        trade_id: String,
+        /// The error logs returned by the service.
+        error_logs: Vec<String>,
+    },
+}
+
+/// Field error
+///
+/// This struct is used to report a field of a request that failed the client side validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// The name of the field, as in the request.
+    pub field: String,
+    /// Why the value is invalid.
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Transaction error
+///
+/// This enum is used to define why a transaction returned by the service cannot be decoded,
+/// signed or encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TransactionError {
+    /// The transaction is not valid base64.
+    Base64(String),
+    /// The bytes are not a bincode encoded versioned transaction.
+    Decode(String),
+    /// The transaction cannot be encoded.
+    Encode(String),
+    /// The signer is not one of the required signers of the transaction.
+    UnexpectedSigner(String),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::Base64(msg) => write!(f, "invalid base64: {}", msg),
+            TransactionError::Decode(msg) => write!(f, "invalid transaction: {}", msg),
+            TransactionError::Encode(msg) => write!(f, "cannot encode transaction: {}", msg),
+            TransactionError::UnexpectedSigner(pubkey) => {
+                write!(f, "{} is not a required signer", pubkey)
+            }
+        }
+    }
+}
+
+/// Stop reason
+///
+/// This enum is used to define why a trade status watcher stopped before the trade completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The cancellation token was cancelled.
+    Cancelled,
+    /// The deadline expired.
+    DeadlineExceeded,
+    /// The maximum number of attempts was reached.
+    MaxAttemptsReached,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Cancelled => write!(f, "cancelled"),
+            StopReason::DeadlineExceeded => write!(f, "deadline exceeded"),
+            StopReason::MaxAttemptsReached => write!(f, "max attempts reached"),
+        }
+    }
+}
+
+impl Error {
+    /// Returns the gRPC status code, if the error comes from the service.
+    pub fn code(&self) -> Option<Code> {
+        match self {
+            Error::Grpc { code, .. } => Some(*code),
+            Error::Timeout { .. } => Some(Code::DeadlineExceeded),
+            _ => None,
+        }
+    }
+
+    /// Returns the tracking id of the request that failed, if known.
+    pub fn tracking_id(&self) -> Option<&str> {
+        match self {
+            Error::Timeout { tracking_id } | Error::Grpc { tracking_id, .. } => {
+                tracking_id.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the metadata attached to the error status, if the error comes from the service.
+    pub fn metadata(&self) -> Option<&MetadataMap> {
+        match self {
+            Error::Grpc { metadata, .. } => Some(metadata),
+            _ => None,
+        }
+    }
+
+    /// Whether the failure is transient and the call can be retried.
+    ///
+    /// The status codes are checked against the retryable codes of the default `RetryPolicy`,
+    /// not against the policy configured on the client.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::ConnectionFailed(_) | Error::StreamEnded => true,
+            _ => self
+                .code()
+                .is_some_and(|code| RetryPolicy::default().is_retryable(code)),
+        }
+    }
+
+    /// Returns the last status observed by a watcher that stopped before the trade completed.
+    pub fn last_response(&self) -> Option<&CheckTradeStatusResponse> {
+        match self {
+            Error::WatchStopped { last_response, .. } => last_response.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the requested resource (e.g. a trade) does not exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Error::Grpc {
+                code: Code::NotFound,
+                ..
+            }
+        )
+    }
+
+    /// Whether the service rejected the request as invalid.
+    pub fn is_invalid_argument(&self) -> bool {
+        matches!(
+            self,
+            Error::Grpc {
+                code: Code::InvalidArgument,
+                ..
+            }
+        )
+    }
+
+    /// Returns the fields that failed the client side validation, if the request was rejected.
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            Error::Validation(errors) => errors,
+            _ => &[],
+        }
+    }
+
+    /// Attaches the tracking id of the request to the error.
+    pub(crate) fn with_tracking_id(mut self, id: &str) -> Self {
+        match &mut self {
+            Error::Timeout { tracking_id } | Error::Grpc { tracking_id, .. } => {
+                *tracking_id = Some(id.to_string());
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Display implementation for the SDK error.
+///
+/// This implementation provides a human-readable representation of the SDK error.
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidConfig(msg) => write!(f, "Invalid config: {}", msg),
+            Error::InvalidUri(msg) => write!(f, "Invalid URI: {}", msg),
+            Error::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            Error::Timeout { tracking_id } => {
+                write!(f, "Request timed out")?;
+                if let Some(tracking_id) = tracking_id {
+                    write!(f, " (tracking id: {})", tracking_id)?;
+                }
+                Ok(())
+            }
+            Error::Grpc {
+                code,
+                message,
+                tracking_id,
+                ..
+            } => {
+                write!(f, "gRPC error: {:?}: {}", code, message)?;
+                if let Some(tracking_id) = tracking_id {
+                    write!(f, " (tracking id: {})", tracking_id)?;
+                }
+                Ok(())
+            }
+            Error::ChannelClosed(msg) => write!(f, "Channel closed: {}", msg),
+            Error::WatchStopped {
+                reason,
+                last_response,
+            } => {
+                write!(f, "Trade status watcher stopped: {}", reason)?;
+                if let Some(last_response) = last_response {
+                    write!(f, " (last status: {:?})", last_response.status)?;
+                }
+                Ok(())
+            }
+            Error::StreamEnded => write!(f, "Trade status stream ended unexpectedly"),
+            Error::Store(msg) => write!(f, "Trade store error: {}", msg),
+            Error::Validation(errors) => {
+                write!(f, "Invalid request: ")?;
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
+            Error::InvalidAddress(msg) => write!(f, "Invalid address: {}", msg),
+            Error::InvalidAmount(msg) => write!(f, "Invalid amount: {}", msg),
+            Error::Signing(msg) => write!(f, "Signing failed: {}", msg),
+            Error::Transaction(error) => write!(f, "Transaction error: {}", error),
+            Error::Verification(msg) => write!(f, "Transaction verification failed: {}", msg),
+            Error::StaleQuote { age, max_age } => write!(
+                f,
+                "Quote is {:?} old, at most {:?} is allowed",
+                age, max_age
+            ),
+            Error::BlockhashExpired { refreshes } => write!(
+                f,
+                "Blockhash expired before the transaction was sent, after {} refreshes",
+                refreshes
+            ),
+            Error::SendFailed {
+                trade_id,
+                error_logs,
+            } => write!(
+                f,
+                "Failed to send the transaction of trade {}: {}",
+                trade_id,
+                error_logs.join("; ")
+            ),
+        }
+    }
+}
+
+/// Convert from tonic::Status to Error
+///
+/// A status caused by the client side timeout is reported as `Error::Timeout`.
+impl From<tonic::Status> for Error {
+    fn from(status: tonic::Status) -> Self {
+        let mut source = std::error::Error::source(&status);
+        while let Some(err) = source {
+            if err.is::<tonic::TimeoutExpired>() {
+                return Error::Timeout { tracking_id: None };
+            }
+            source = err.source();
+        }
+        Error::Grpc {
+            code: status.code(),
+            message: status.message().to_string(),
+            metadata: Box::new(status.metadata().clone()),
+            tracking_id: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test error classification
+    ///
+    /// This is used to test the helpers used to branch on failures.
+    fn test_error_classification() {
+        let not_found: Error = tonic::Status::not_found("trade not found").into();
+        assert!(not_found.is_not_found());
+        assert!(!not_found.is_retryable());
+        assert_eq!(not_found.code(), Some(Code::NotFound));
+
+        let unavailable = Error::from(tonic::Status::unavailable("down")).with_tracking_id("abc");
+        assert!(unavailable.is_retryable());
+        assert_eq!(unavailable.tracking_id(), Some("abc"));
+
+        let timeout = Error::Timeout { tracking_id: None };
+        assert!(timeout.is_retryable());
+        assert_eq!(timeout.code(), Some(Code::DeadlineExceeded));
+
+        assert!(Error::ConnectionFailed("refused".to_string()).is_retryable());
+        assert!(!Error::InvalidConfig("bad".to_string()).is_retryable());
+    }
+}
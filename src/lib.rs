@@ -19,7 +19,7 @@
 //! To use the SDK, you need to create a client and then use the client to get a quote. To create the client you need a URL we will provide you with.
 //!
 //!   A very simple starter example, which just outputs a quote:
-//!   ```rust
+//!   ```rust,no_run
 //!   use eyre::Result;
 //!   use darklake_sdk_off_chain as sdk;
 //!   use tracing::*;
@@ -32,18 +32,20 @@
 //!   let config = sdk::Config::builder()
 //!     .network(sdk::Network::Mainnet)
 //!     .url("http://localhost:50051")?
-//!     .is_final_url(true)
+//!     .final_url(true)
 //!     .build()?;
 
-//!   let mut client = sdk::Client::new(config).await?;
+//!   let client = sdk::Client::new(config).await?;
 
 //!   let quote = client
-//!     .get_quote(sdk::QuoteRequest {
-//!         token_mint_x: "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".to_string(),
-//!         token_mint_y: "So11111111111111111111111111111111111111112".to_string(),
-//!         amount_in: 1000000000000000000,
-//!         is_swap_x_to_y: true,
-//!     })
+//!     .get_quote(
+//!         sdk::QuoteRequest::builder(
+//!             "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX".parse()?,
+//!             "So11111111111111111111111111111111111111112".parse()?,
+//!             1000000000000000000,
+//!         )
+//!         .build()?,
+//!     )
 //!   .await?;
 
 //!   info!("Quote: {:?}", quote);
@@ -59,18 +61,48 @@ mod integrations_pb {
 
 mod client;
 mod core;
+mod error;
 mod models;
 
 pub use client::Client;
 pub use client::ClientType;
-pub use core::config::{Config, Network};
+pub use client::tracker::{TradeTracker, TradeTrackerBuilder};
+pub use client::{TradeEventStream, TradeStatusStream};
+pub use core::auth::{Credentials, StaticTokenProvider, TokenProvider};
+pub use core::config::{Config, ConfigBuilder, Network};
+#[cfg(feature = "solana")]
+pub use core::describe::{
+    AccountDescription, ComputeBudget, InstructionDescription, TransactionDescription,
+    describe_transaction,
+};
+pub use core::retry::{RetryPolicy, RetryPolicyBuilder};
+pub use core::signer::TransactionSigner;
+pub use core::store::{
+    InMemoryTradeStore, JsonLinesTradeStore, PendingTrade, TradeRecord, TradeStore,
+};
+#[cfg(feature = "solana")]
+pub use core::transaction::{
+    SolanaSigner, UnsignedTransaction, decode_transaction, encode_transaction, sign_transaction,
+};
+#[cfg(feature = "solana")]
+pub use core::verify::{
+    VerificationPolicy, VerificationPolicyBuilder, verify_unsigned_add_liquidity,
+    verify_unsigned_init_pool, verify_unsigned_remove_liquidity, verify_unsigned_swap,
+};
+pub use error::{Error, FieldError, Result, StopReason, TransactionError};
 pub use models::{
-    AddLiquidityRequest, AddLiquidityResponse, CheckTradeStatusRequest,
-    CheckTradeStatusRequestBuilder, CheckTradeStatusResponse, CreateUnsignedTransactionRequest,
+    AddLiquidityRequest, AddLiquidityRequestBuilder, AddLiquidityResponse, BLOCKHASH_VALIDITY,
+    CheckTradeStatusBatchRequest, CheckTradeStatusRequest, CheckTradeStatusRequestBuilder,
+    CheckTradeStatusResponse, CreateUnsignedTransactionRequest,
     CreateUnsignedTransactionRequestBuilder, CreateUnsignedTransactionResponse,
-    GetTradesListByUserRequest, GetTradesListByUserResponse, InitPoolRequest, InitPoolResponse,
-    QuoteRequest, QuoteResponse, RemoveLiquidityRequest, RemoveLiquidityResponse,
+    GetTradesListByUserRequest, GetTradesListByUserResponse, InitPoolRequest,
+    InitPoolRequestBuilder, InitPoolResponse, Mint, PhaseTiming, PhaseTimings, PollStrategy,
+    PriorityLevel, QuoteRequest, QuoteRequestBuilder, QuoteResponse, RemoveLiquidityRequest,
+    RemoveLiquidityRequestBuilder, RemoveLiquidityResponse,
     SendSignedTransactionAndCheckStatusRequest, SendSignedTransactionAndCheckStatusRequestBuilder,
     SendSignedTransactionRequest, SendSignedTransactionRequestBuilder,
-    SendSignedTransactionResponse, TradeStatus,
+    SendSignedTransactionResponse, StatusTransition, SwapParams, SwapParamsBuilder, SwapResult,
+    TokenAmount, TokenMetadata, Trade, TradeEvent, TradeStatus, Validate, WalletAddress,
 };
+pub use tokio_util::sync::CancellationToken;
+pub use tonic::Code;
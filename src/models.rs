@@ -1,15 +1,27 @@
+pub mod address;
+pub mod amount;
 pub mod enums;
+pub mod event;
 pub mod liquidity;
+pub mod poll;
 pub mod pool;
 pub mod quote;
 pub mod signed_tx;
+pub mod swap;
 pub mod trade;
 pub mod unsigned_tx;
+pub mod validation;
 
+pub use address::*;
+pub use amount::*;
 pub use enums::*;
+pub use event::*;
 pub use liquidity::*;
+pub use poll::*;
 pub use pool::*;
 pub use quote::*;
 pub use signed_tx::*;
+pub use swap::*;
 pub use trade::*;
 pub use unsigned_tx::*;
+pub use validation::*;
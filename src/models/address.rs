@@ -0,0 +1,145 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::{fmt, str::FromStr};
+
+use crate::error::{Error, Result};
+
+/// The length of a Solana public key, in bytes.
+const PUBKEY_LEN: usize = 32;
+
+/// Decodes a base58 encoded 32 bytes public key.
+fn decode(value: &str) -> Result<[u8; PUBKEY_LEN]> {
+    let mut bytes = [0u8; PUBKEY_LEN];
+    match bs58::decode(value).onto(&mut bytes) {
+        Ok(PUBKEY_LEN) => Ok(bytes),
+        Ok(len) => Err(Error::InvalidAddress(format!(
+            "{} decodes to {} bytes instead of {}",
+            value, len, PUBKEY_LEN
+        ))),
+        Err(e) => Err(Error::InvalidAddress(format!("{}: {}", value, e))),
+    }
+}
+
+/// Defines a public key newtype, so the different kinds of addresses cannot be swapped.
+macro_rules! address {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name([u8; PUBKEY_LEN]);
+
+        impl $name {
+            /// Creates the address from the bytes of the public key.
+            pub const fn new(bytes: [u8; PUBKEY_LEN]) -> Self {
+                Self(bytes)
+            }
+
+            /// Returns the bytes of the public key.
+            pub const fn to_bytes(&self) -> [u8; PUBKEY_LEN] {
+                self.0
+            }
+        }
+
+        /// FromStr implementation for the address.
+        ///
+        /// This implementation parses a base58 encoded 32 bytes public key, and returns
+        /// `Error::InvalidAddress` otherwise.
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(value: &str) -> Result<Self> {
+                decode(value).map(Self)
+            }
+        }
+
+        /// Display implementation for the address.
+        ///
+        /// This implementation provides the base58 encoding of the public key.
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&bs58::encode(self.0).into_string())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self)
+            }
+        }
+
+        #[cfg(feature = "solana")]
+        impl From<solana_pubkey::Pubkey> for $name {
+            fn from(pubkey: solana_pubkey::Pubkey) -> Self {
+                Self(pubkey.to_bytes())
+            }
+        }
+
+        #[cfg(feature = "solana")]
+        impl From<$name> for solana_pubkey::Pubkey {
+            fn from(address: $name) -> Self {
+                solana_pubkey::Pubkey::new_from_array(address.0)
+            }
+        }
+    };
+}
+
+address!(
+    /// Mint
+    ///
+    /// This struct is used to hold the address of a token mint.
+    Mint
+);
+
+address!(
+    /// Wallet address
+    ///
+    /// This struct is used to hold the address of a user wallet.
+    WalletAddress
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test address parsing
+    ///
+    /// This is used to test that only base58 strings decoding to 32 bytes are accepted, and that
+    /// a parsed address is displayed unchanged.
+    fn test_parse_address() {
+        for value in [
+            "So11111111111111111111111111111111111111112",
+            "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX",
+        ] {
+            assert_eq!(value.parse::<Mint>().unwrap().to_string(), value);
+        }
+        assert!(
+            "So1111111111111111111111111111111111111111"
+                .parse::<Mint>()
+                .is_err()
+        );
+        assert!(matches!(
+            "0OIl".parse::<WalletAddress>(),
+            Err(Error::InvalidAddress(_))
+        ));
+        assert!("".parse::<WalletAddress>().is_err());
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    /// Test Solana conversions
+    ///
+    /// This is used to test that an address round trips through `solana_pubkey::Pubkey`.
+    fn test_solana_pubkey_round_trip() {
+        let mint: Mint = "So11111111111111111111111111111111111111112"
+            .parse()
+            .unwrap();
+        let pubkey = solana_pubkey::Pubkey::from(mint);
+
+        assert_eq!(pubkey.to_string(), mint.to_string());
+        assert_eq!(Mint::from(pubkey), mint);
+    }
+}
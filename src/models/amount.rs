@@ -0,0 +1,176 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+/// Token amount
+///
+/// This struct is used to hold an amount in the raw units of a token along with the decimals of
+/// the token, so it can be parsed from and displayed in human units (e.g. `1.5` for
+/// `1_500_000_000` raw units of a token with 9 decimals).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenAmount {
+    /// The amount in raw units, as sent to the Darklake Integrations service.
+    pub raw: u64,
+    /// The decimals of the token.
+    pub decimals: u8,
+}
+
+impl TokenAmount {
+    pub fn new(raw: u64, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// Parses an amount in human units, e.g. `1.5`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidAmount` if the value is not a positive decimal number, has more
+    /// fractional digits than the token decimals or does not fit in a `u64` once scaled.
+    pub fn parse(value: &str, decimals: u8) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidAmount(format!("{}: {}", value, reason));
+        let (integer, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid("expected a decimal number"));
+        }
+        if !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid("expected a decimal number"));
+        }
+        if fraction.len() > decimals as usize {
+            return Err(invalid(&format!("more than {} decimals", decimals)));
+        }
+        let digits = format!(
+            "{}{:0<width$}",
+            integer,
+            fraction,
+            width = decimals as usize
+        );
+        let digits = digits.trim_start_matches('0');
+        let raw = if digits.is_empty() {
+            0
+        } else {
+            digits.parse().map_err(|_| invalid("too large"))?
+        };
+        Ok(Self { raw, decimals })
+    }
+
+    /// Adds two amounts of the same token.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` on overflow or if the amounts have different decimals.
+    pub fn checked_add(self, other: TokenAmount) -> Option<TokenAmount> {
+        (self.decimals == other.decimals).then_some(())?;
+        Some(Self::new(self.raw.checked_add(other.raw)?, self.decimals))
+    }
+
+    /// Subtracts an amount of the same token.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` on underflow or if the amounts have different decimals.
+    pub fn checked_sub(self, other: TokenAmount) -> Option<TokenAmount> {
+        (self.decimals == other.decimals).then_some(())?;
+        Some(Self::new(self.raw.checked_sub(other.raw)?, self.decimals))
+    }
+
+    /// Multiplies the amount by a whole number.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` on overflow.
+    pub fn checked_mul(self, factor: u64) -> Option<TokenAmount> {
+        Some(Self::new(self.raw.checked_mul(factor)?, self.decimals))
+    }
+}
+
+/// Display implementation for the token amount.
+///
+/// This implementation provides the amount in human units, without trailing zeros
+/// (e.g. `1.5`, `0.000001` or `42`).
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = self.decimals as usize;
+        let digits = format!("{:0>width$}", self.raw, width = decimals + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            f.pad(integer)
+        } else {
+            f.pad(&format!("{}.{}", integer, fraction))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test amount parsing
+    ///
+    /// This is used to test that human units are scaled with the token decimals and displayed
+    /// back unchanged.
+    fn test_parse_and_display() {
+        for (value, decimals, raw, display) in [
+            ("1.5", 9, 1_500_000_000, "1.5"),
+            ("0.000001", 6, 1, "0.000001"),
+            ("42", 0, 42, "42"),
+            ("42.", 2, 4200, "42"),
+            (".25", 2, 25, "0.25"),
+            ("0", 9, 0, "0"),
+            ("18446744073709551615", 0, u64::MAX, "18446744073709551615"),
+        ] {
+            let amount = TokenAmount::parse(value, decimals).unwrap();
+            assert_eq!(amount.raw, raw, "{}", value);
+            assert_eq!(amount.to_string(), display);
+        }
+
+        for (value, decimals) in [
+            ("1.5", 0),
+            ("1.0000000001", 9),
+            ("-1", 9),
+            ("1,5", 9),
+            ("", 9),
+            (".", 9),
+            ("18446744073709551616", 0),
+            ("18446744073.709551616", 9),
+        ] {
+            assert!(
+                matches!(
+                    TokenAmount::parse(value, decimals),
+                    Err(Error::InvalidAmount(_))
+                ),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    /// Test checked arithmetic
+    ///
+    /// This is used to test that overflows and amounts of different decimals are rejected.
+    fn test_checked_arithmetic() {
+        let amount = TokenAmount::new(1_500, 3);
+
+        assert_eq!(
+            amount.checked_add(TokenAmount::new(500, 3)),
+            Some(TokenAmount::new(2_000, 3))
+        );
+        assert_eq!(amount.checked_add(TokenAmount::new(500, 6)), None);
+        assert_eq!(amount.checked_add(TokenAmount::new(u64::MAX, 3)), None);
+        assert_eq!(amount.checked_sub(TokenAmount::new(1_501, 3)), None);
+        assert_eq!(amount.checked_mul(2), Some(TokenAmount::new(3_000, 3)));
+        assert_eq!(amount.checked_mul(u64::MAX), None);
+    }
+}
@@ -4,12 +4,16 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
-use crate::integrations_pb::TradeStatus as ProtoTradeStatus;
+use serde::{Deserialize, Serialize};
+
+use crate::integrations_pb::{
+    PriorityLevel as ProtoPriorityLevel, TradeStatus as ProtoTradeStatus,
+};
 
 /// Trade status
 ///
 /// This enum is used to define the status of a trade.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TradeStatus {
     /// The trade is unsigned.
     Unsigned,
@@ -25,6 +29,66 @@ pub enum TradeStatus {
     Cancelled,
     /// The trade failed for a system error.
     Failed,
+    /// A status this version of the SDK does not know, with its raw value.
+    ///
+    /// It is treated as pending, so the watchers keep following the trade.
+    Unknown(i32),
+}
+
+impl TradeStatus {
+    /// Whether the trade reached a status it will not leave anymore
+    /// (`Settled`, `Slashed`, `Cancelled` or `Failed`).
+    pub fn is_terminal(&self) -> bool {
+        !self.is_pending()
+    }
+
+    /// Whether the trade completed successfully, i.e. it is `Settled`.
+    pub fn is_success(&self) -> bool {
+        matches!(self, TradeStatus::Settled)
+    }
+
+    /// Whether the trade is still in progress (`Unsigned`, `Signed`, `Confirmed` or `Unknown`).
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            TradeStatus::Unsigned
+                | TradeStatus::Signed
+                | TradeStatus::Confirmed
+                | TradeStatus::Unknown(_)
+        )
+    }
+
+    /// Whether a trade can move from this status to `next`.
+    ///
+    /// A trade only moves forward, `Unsigned` then `Signed` then `Confirmed` then a terminal
+    /// status, and never leaves a terminal status. Intermediate statuses can be skipped, since
+    /// a watcher may miss a status that did not last long. The position of an `Unknown` status
+    /// in the lifecycle is not known, so any transition from or to it is accepted, as long as the
+    /// trade is not terminal.
+    pub fn can_transition_to(&self, next: &TradeStatus) -> bool {
+        if !self.is_pending() || self == next {
+            return false;
+        }
+        match (self.stage(), next.stage()) {
+            (Some(stage), Some(next)) => next > stage,
+            _ => true,
+        }
+    }
+
+    /// Returns the position of the status in the lifecycle of a trade, `None` if it is unknown.
+    fn stage(&self) -> Option<u8> {
+        let stage = match self {
+            TradeStatus::Unsigned => 0,
+            TradeStatus::Signed => 1,
+            TradeStatus::Confirmed => 2,
+            TradeStatus::Settled
+            | TradeStatus::Slashed
+            | TradeStatus::Cancelled
+            | TradeStatus::Failed => 3,
+            TradeStatus::Unknown(_) => return None,
+        };
+        Some(stage)
+    }
 }
 
 /// Convert from ProtoTradeStatus to TradeStatus
@@ -57,7 +121,82 @@ impl From<i32> for TradeStatus {
             4 => TradeStatus::Slashed,
             5 => TradeStatus::Cancelled,
             6 => TradeStatus::Failed,
-            _ => TradeStatus::Failed,
+            _ => TradeStatus::Unknown(status),
+        }
+    }
+}
+
+/// Priority level
+///
+/// This enum is used to ask the Darklake Integrations service for a priority fee matching the
+/// recent fees of the network, instead of an explicit compute unit price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriorityLevel {
+    /// A fee below the recent median, for transactions that can wait.
+    Low,
+    /// A fee around the recent median.
+    Medium,
+    /// A fee above the recent median, for transactions that must land quickly.
+    High,
+    /// The service picks the level.
+    Auto,
+}
+
+impl PriorityLevel {
+    /// Converts the raw value of an API message, `None` if it is unspecified or unknown.
+    pub(crate) fn from_proto(priority_level: i32) -> Option<Self> {
+        match ProtoPriorityLevel::try_from(priority_level).ok()? {
+            ProtoPriorityLevel::Unspecified => None,
+            ProtoPriorityLevel::Low => Some(PriorityLevel::Low),
+            ProtoPriorityLevel::Medium => Some(PriorityLevel::Medium),
+            ProtoPriorityLevel::High => Some(PriorityLevel::High),
+            ProtoPriorityLevel::Auto => Some(PriorityLevel::Auto),
         }
     }
+
+    /// Converts an optional level to the raw value of an API message.
+    pub(crate) fn to_proto(priority_level: Option<Self>) -> i32 {
+        let priority_level = match priority_level {
+            None => ProtoPriorityLevel::Unspecified,
+            Some(PriorityLevel::Low) => ProtoPriorityLevel::Low,
+            Some(PriorityLevel::Medium) => ProtoPriorityLevel::Medium,
+            Some(PriorityLevel::High) => ProtoPriorityLevel::High,
+            Some(PriorityLevel::Auto) => ProtoPriorityLevel::Auto,
+        };
+        priority_level as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test trade status transitions
+    ///
+    /// This is used to test that a trade only moves forward and never leaves a terminal status.
+    fn test_can_transition_to() {
+        assert!(TradeStatus::Unsigned.can_transition_to(&TradeStatus::Signed));
+        assert!(TradeStatus::Signed.can_transition_to(&TradeStatus::Settled));
+        assert!(TradeStatus::Confirmed.can_transition_to(&TradeStatus::Slashed));
+        assert!(!TradeStatus::Confirmed.can_transition_to(&TradeStatus::Signed));
+        assert!(!TradeStatus::Signed.can_transition_to(&TradeStatus::Signed));
+        assert!(!TradeStatus::Settled.can_transition_to(&TradeStatus::Signed));
+        assert!(!TradeStatus::Settled.can_transition_to(&TradeStatus::Failed));
+        assert!(TradeStatus::Confirmed.can_transition_to(&TradeStatus::Unknown(7)));
+        assert!(TradeStatus::Unknown(7).can_transition_to(&TradeStatus::Signed));
+        assert!(!TradeStatus::Settled.can_transition_to(&TradeStatus::Unknown(7)));
+    }
+
+    #[test]
+    /// Test unknown trade status
+    ///
+    /// This is used to test that an unknown raw value is preserved and treated as pending.
+    fn test_unknown_status_is_preserved() {
+        let status = TradeStatus::from(42);
+
+        assert_eq!(status, TradeStatus::Unknown(42));
+        assert!(status.is_pending());
+        assert!(!status.is_terminal());
+    }
 }
@@ -0,0 +1,86 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::time::{Duration, SystemTime};
+
+use crate::models::{CheckTradeStatusResponse, SendSignedTransactionResponse, TradeStatus};
+
+/// Trade event
+///
+/// This enum is used to report the progress of a trade after the signed transaction is sent.
+#[derive(Debug, Clone)]
+pub enum TradeEvent {
+    /// The signed transaction was sent to the Darklake Integrations service.
+    Submitted(SendSignedTransactionResponse),
+    /// The trade moved to a new status. The transition to the terminal status is reported too.
+    StatusChanged(StatusTransition),
+    /// The service reported a status the trade cannot move to from the previous one, e.g.
+    /// `Confirmed` back to `Signed`. It is reported instead of `StatusChanged`, and the watcher
+    /// then follows the reported status.
+    Anomaly(StatusTransition),
+    /// The trade reached a terminal status. This is always the last event.
+    Terminal {
+        /// The terminal status of the trade.
+        response: CheckTradeStatusResponse,
+        /// The time spent in each status before reaching the terminal one.
+        timings: PhaseTimings,
+    },
+}
+
+/// Status transition
+///
+/// This struct is used to report that a trade moved from one status to another.
+#[derive(Debug, Clone)]
+pub struct StatusTransition {
+    /// The trade id.
+    pub trade_id: String,
+    /// The previous status, `None` for the first status observed by the watcher.
+    pub from: Option<TradeStatus>,
+    /// The new status.
+    pub to: TradeStatus,
+    /// When the new status was observed.
+    pub at: SystemTime,
+    /// The time spent in the previous status, or since the watcher started for the first status.
+    pub elapsed: Duration,
+}
+
+/// Phase timing
+///
+/// This struct holds the time a trade spent in one status.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseTiming {
+    /// The status.
+    pub status: TradeStatus,
+    /// The time spent in the status.
+    pub duration: Duration,
+}
+
+/// Phase timings
+///
+/// This struct holds the time a trade spent in each status, in the order the statuses were observed
+/// (e.g. `Signed` then `Confirmed`). The first phase is measured from the moment the watcher observed
+/// it, so it can be shorter than the actual time the trade spent in it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhaseTimings {
+    /// The phases, in order.
+    pub phases: Vec<PhaseTiming>,
+}
+
+impl PhaseTimings {
+    /// Returns the time spent in the given status, if the trade went through it.
+    pub fn duration(&self, status: &TradeStatus) -> Option<Duration> {
+        self.phases
+            .iter()
+            .filter(|phase| &phase.status == status)
+            .map(|phase| phase.duration)
+            .reduce(|total, duration| total + duration)
+    }
+
+    /// Returns the time spent in all the phases.
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|phase| phase.duration).sum()
+    }
+}
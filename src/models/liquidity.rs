@@ -4,11 +4,21 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
-use crate::integrations_pb::{
-    AddLiquidityRequest as ProtoAddLiquidityRequest,
-    AddLiquidityResponse as ProtoAddLiquidityResponse,
-    RemoveLiquidityRequest as ProtoRemoveLiquidityRequest,
-    RemoveLiquidityResponse as ProtoRemoveLiquidityResponse,
+use rand::distr::{Alphanumeric, SampleString};
+
+use crate::{
+    error::Result,
+    integrations_pb::{
+        AddLiquidityRequest as ProtoAddLiquidityRequest,
+        AddLiquidityResponse as ProtoAddLiquidityResponse,
+        RemoveLiquidityRequest as ProtoRemoveLiquidityRequest,
+        RemoveLiquidityResponse as ProtoRemoveLiquidityResponse,
+    },
+    models::{
+        address::{Mint, WalletAddress},
+        enums::PriorityLevel,
+        validation::{ProtoRules, Validate, Validator},
+    },
 };
 
 /// Add liquidity request
@@ -17,11 +27,11 @@ use crate::integrations_pb::{
 #[derive(Debug, Clone)]
 pub struct AddLiquidityRequest {
     /// The mint address of the token X.
-    pub token_mint_x: String,
+    pub token_mint_x: Mint,
     /// The mint address of the token Y.
-    pub token_mint_y: String,
+    pub token_mint_y: Mint,
     /// The wallet address of the user.
-    pub user_address: String,
+    pub user_address: WalletAddress,
     /// The desired amount of pool liquidity tokens.
     pub amount_lp: u64,
     /// The maximum amount of token X to add.
@@ -32,6 +42,164 @@ pub struct AddLiquidityRequest {
     pub ref_code: String,
     /// The label to attach, 10 chars max.
     pub label: String,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    /// The tracking id for the call. This id helps in tracing the call on the server side, making it simple to debug issues.
+    pub tracking_id: String,
+}
+
+impl AddLiquidityRequest {
+    pub fn builder(
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
+        amount_lp: u64,
+        max_amount_x: u64,
+        max_amount_y: u64,
+    ) -> AddLiquidityRequestBuilder {
+        AddLiquidityRequestBuilder::new(
+            user_address,
+            token_mint_x,
+            token_mint_y,
+            amount_lp,
+            max_amount_x,
+            max_amount_y,
+        )
+    }
+}
+
+/// Validate implementation for the add liquidity request.
+///
+/// The rules of `api.proto` are checked first, then the mints must be distinct and a priority
+/// fee cannot be combined with a priority level.
+impl Validate for AddLiquidityRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoAddLiquidityRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .distinct_mints(&self.token_mint_x, &self.token_mint_y)
+            .priority(self.priority_fee_micro_lamports, self.priority_level)
+            .finish()
+    }
+}
+
+/// AddLiquidityRequestBuilder
+///
+/// This struct is used to build an AddLiquidityRequest.
+#[derive(Debug, Clone)]
+pub struct AddLiquidityRequestBuilder {
+    /// The mint address of the token X.
+    pub token_mint_x: Mint,
+    /// The mint address of the token Y.
+    pub token_mint_y: Mint,
+    /// The wallet address of the user.
+    pub user_address: WalletAddress,
+    /// The desired amount of pool liquidity tokens.
+    pub amount_lp: u64,
+    /// The maximum amount of token X to add.
+    pub max_amount_x: u64,
+    /// The maximum amount of token Y to add.
+    pub max_amount_y: u64,
+    /// The referral code.
+    pub ref_code: Option<String>,
+    /// The label to attach, 10 chars max.
+    pub label: Option<String>,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    /// The tracking id for the call.
+    pub tracking_id: Option<String>,
+}
+
+impl AddLiquidityRequestBuilder {
+    pub fn new(
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
+        amount_lp: u64,
+        max_amount_x: u64,
+        max_amount_y: u64,
+    ) -> Self {
+        Self {
+            token_mint_x,
+            token_mint_y,
+            user_address,
+            amount_lp,
+            max_amount_x,
+            max_amount_y,
+            ref_code: None,
+            label: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            priority_level: None,
+            tracking_id: None,
+        }
+    }
+
+    pub fn ref_code(mut self, ref_code: &str) -> Self {
+        self.ref_code = Some(ref_code.to_string());
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn tracking_id(mut self, tracking_id: &str) -> Self {
+        self.tracking_id = Some(tracking_id.to_string());
+        self
+    }
+
+    /// Sets the compute unit price, in micro lamports.
+    pub fn priority_fee_micro_lamports(mut self, priority_fee_micro_lamports: u64) -> Self {
+        self.priority_fee_micro_lamports = Some(priority_fee_micro_lamports);
+        self
+    }
+
+    /// Sets the compute unit limit.
+    pub fn compute_unit_limit(mut self, compute_unit_limit: u32) -> Self {
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self
+    }
+
+    /// Sets the priority level the service resolves into a compute unit price.
+    pub fn priority_level(mut self, priority_level: PriorityLevel) -> Self {
+        self.priority_level = Some(priority_level);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` with every offending field if the request is invalid.
+    pub fn build(self) -> Result<AddLiquidityRequest> {
+        let request = AddLiquidityRequest {
+            token_mint_x: self.token_mint_x,
+            token_mint_y: self.token_mint_y,
+            user_address: self.user_address,
+            amount_lp: self.amount_lp,
+            max_amount_x: self.max_amount_x,
+            max_amount_y: self.max_amount_y,
+            ref_code: self.ref_code.unwrap_or_default(),
+            label: self.label.unwrap_or_default(),
+            priority_fee_micro_lamports: self.priority_fee_micro_lamports,
+            compute_unit_limit: self.compute_unit_limit,
+            priority_level: self.priority_level,
+            tracking_id: self
+                .tracking_id
+                .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12)),
+        };
+        request.validate()?;
+        Ok(request)
+    }
 }
 
 /// Add liquidity response
@@ -41,6 +209,12 @@ pub struct AddLiquidityRequest {
 pub struct AddLiquidityResponse {
     /// Base64 encoded unsigned transaction to send to the wallet for sign & execute.
     pub unsigned_transaction: String,
+    /// The compute unit price chosen by the service, in micro lamports.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit chosen by the service.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the compute unit price was resolved from.
+    pub priority_level: Option<PriorityLevel>,
 }
 
 /// Convert from ProtoAddLiquidityResponse to AddLiquidityResponse
@@ -50,6 +224,9 @@ impl From<ProtoAddLiquidityResponse> for AddLiquidityResponse {
     fn from(response: ProtoAddLiquidityResponse) -> Self {
         Self {
             unsigned_transaction: response.unsigned_transaction,
+            priority_fee_micro_lamports: response.priority_fee_micro_lamports,
+            compute_unit_limit: response.compute_unit_limit,
+            priority_level: PriorityLevel::from_proto(response.priority_level),
         }
     }
 }
@@ -60,14 +237,17 @@ impl From<ProtoAddLiquidityResponse> for AddLiquidityResponse {
 impl From<AddLiquidityRequest> for ProtoAddLiquidityRequest {
     fn from(request: AddLiquidityRequest) -> Self {
         Self {
-            token_mint_x: request.token_mint_x,
-            token_mint_y: request.token_mint_y,
-            user_address: request.user_address,
+            token_mint_x: request.token_mint_x.to_string(),
+            token_mint_y: request.token_mint_y.to_string(),
+            user_address: request.user_address.to_string(),
             amount_lp: request.amount_lp,
             max_amount_x: request.max_amount_x,
             max_amount_y: request.max_amount_y,
             ref_code: request.ref_code,
             label: request.label,
+            priority_fee_micro_lamports: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+            priority_level: PriorityLevel::to_proto(request.priority_level),
         }
     }
 }
@@ -78,11 +258,11 @@ impl From<AddLiquidityRequest> for ProtoAddLiquidityRequest {
 #[derive(Debug, Clone)]
 pub struct RemoveLiquidityRequest {
     /// The mint address of the token X.
-    pub token_mint_x: String,
+    pub token_mint_x: Mint,
     /// The mint address of the token Y.
-    pub token_mint_y: String,
+    pub token_mint_y: Mint,
     /// The wallet address of the user.
-    pub user_address: String,
+    pub user_address: WalletAddress,
     /// The desired amount of pool liquidity tokens.
     pub amount_lp: u64,
     /// The minimum amount of token X to remove.
@@ -93,6 +273,164 @@ pub struct RemoveLiquidityRequest {
     pub ref_code: String,
     /// The label to attach, 10 chars max.
     pub label: String,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    /// The tracking id for the call. This id helps in tracing the call on the server side, making it simple to debug issues.
+    pub tracking_id: String,
+}
+
+impl RemoveLiquidityRequest {
+    pub fn builder(
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
+        amount_lp: u64,
+        min_amount_x: u64,
+        min_amount_y: u64,
+    ) -> RemoveLiquidityRequestBuilder {
+        RemoveLiquidityRequestBuilder::new(
+            user_address,
+            token_mint_x,
+            token_mint_y,
+            amount_lp,
+            min_amount_x,
+            min_amount_y,
+        )
+    }
+}
+
+/// Validate implementation for the remove liquidity request.
+///
+/// The rules of `api.proto` are checked first, then the mints must be distinct and a priority
+/// fee cannot be combined with a priority level.
+impl Validate for RemoveLiquidityRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoRemoveLiquidityRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .distinct_mints(&self.token_mint_x, &self.token_mint_y)
+            .priority(self.priority_fee_micro_lamports, self.priority_level)
+            .finish()
+    }
+}
+
+/// RemoveLiquidityRequestBuilder
+///
+/// This struct is used to build a RemoveLiquidityRequest.
+#[derive(Debug, Clone)]
+pub struct RemoveLiquidityRequestBuilder {
+    /// The mint address of the token X.
+    pub token_mint_x: Mint,
+    /// The mint address of the token Y.
+    pub token_mint_y: Mint,
+    /// The wallet address of the user.
+    pub user_address: WalletAddress,
+    /// The desired amount of pool liquidity tokens.
+    pub amount_lp: u64,
+    /// The minimum amount of token X to remove.
+    pub min_amount_x: u64,
+    /// The minimum amount of token Y to remove.
+    pub min_amount_y: u64,
+    /// The referral code.
+    pub ref_code: Option<String>,
+    /// The label to attach, 10 chars max.
+    pub label: Option<String>,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    /// The tracking id for the call.
+    pub tracking_id: Option<String>,
+}
+
+impl RemoveLiquidityRequestBuilder {
+    pub fn new(
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
+        amount_lp: u64,
+        min_amount_x: u64,
+        min_amount_y: u64,
+    ) -> Self {
+        Self {
+            token_mint_x,
+            token_mint_y,
+            user_address,
+            amount_lp,
+            min_amount_x,
+            min_amount_y,
+            ref_code: None,
+            label: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            priority_level: None,
+            tracking_id: None,
+        }
+    }
+
+    pub fn ref_code(mut self, ref_code: &str) -> Self {
+        self.ref_code = Some(ref_code.to_string());
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn tracking_id(mut self, tracking_id: &str) -> Self {
+        self.tracking_id = Some(tracking_id.to_string());
+        self
+    }
+
+    /// Sets the compute unit price, in micro lamports.
+    pub fn priority_fee_micro_lamports(mut self, priority_fee_micro_lamports: u64) -> Self {
+        self.priority_fee_micro_lamports = Some(priority_fee_micro_lamports);
+        self
+    }
+
+    /// Sets the compute unit limit.
+    pub fn compute_unit_limit(mut self, compute_unit_limit: u32) -> Self {
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self
+    }
+
+    /// Sets the priority level the service resolves into a compute unit price.
+    pub fn priority_level(mut self, priority_level: PriorityLevel) -> Self {
+        self.priority_level = Some(priority_level);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` with every offending field if the request is invalid.
+    pub fn build(self) -> Result<RemoveLiquidityRequest> {
+        let request = RemoveLiquidityRequest {
+            token_mint_x: self.token_mint_x,
+            token_mint_y: self.token_mint_y,
+            user_address: self.user_address,
+            amount_lp: self.amount_lp,
+            min_amount_x: self.min_amount_x,
+            min_amount_y: self.min_amount_y,
+            ref_code: self.ref_code.unwrap_or_default(),
+            label: self.label.unwrap_or_default(),
+            priority_fee_micro_lamports: self.priority_fee_micro_lamports,
+            compute_unit_limit: self.compute_unit_limit,
+            priority_level: self.priority_level,
+            tracking_id: self
+                .tracking_id
+                .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12)),
+        };
+        request.validate()?;
+        Ok(request)
+    }
 }
 
 /// Remove liquidity response
@@ -102,6 +440,12 @@ pub struct RemoveLiquidityRequest {
 pub struct RemoveLiquidityResponse {
     /// Base64 encoded unsigned transaction to send to the wallet for sign & execute.
     pub unsigned_transaction: String,
+    /// The compute unit price chosen by the service, in micro lamports.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit chosen by the service.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the compute unit price was resolved from.
+    pub priority_level: Option<PriorityLevel>,
 }
 
 /// Convert from ProtoRemoveLiquidityResponse to RemoveLiquidityResponse    
@@ -111,6 +455,9 @@ impl From<ProtoRemoveLiquidityResponse> for RemoveLiquidityResponse {
     fn from(response: ProtoRemoveLiquidityResponse) -> Self {
         Self {
             unsigned_transaction: response.unsigned_transaction,
+            priority_fee_micro_lamports: response.priority_fee_micro_lamports,
+            compute_unit_limit: response.compute_unit_limit,
+            priority_level: PriorityLevel::from_proto(response.priority_level),
         }
     }
 }
@@ -121,14 +468,17 @@ impl From<ProtoRemoveLiquidityResponse> for RemoveLiquidityResponse {
 impl From<RemoveLiquidityRequest> for ProtoRemoveLiquidityRequest {
     fn from(request: RemoveLiquidityRequest) -> Self {
         Self {
-            token_mint_x: request.token_mint_x,
-            token_mint_y: request.token_mint_y,
-            user_address: request.user_address,
+            token_mint_x: request.token_mint_x.to_string(),
+            token_mint_y: request.token_mint_y.to_string(),
+            user_address: request.user_address.to_string(),
             amount_lp: request.amount_lp,
             min_amount_x: request.min_amount_x,
             min_amount_y: request.min_amount_y,
             ref_code: request.ref_code,
             label: request.label,
+            priority_fee_micro_lamports: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+            priority_level: PriorityLevel::to_proto(request.priority_level),
         }
     }
 }
@@ -0,0 +1,184 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::time::Duration;
+
+use crate::{
+    error::{Error, Result},
+    models::enums::TradeStatus,
+};
+
+/// Poll strategy
+///
+/// This enum is used to define how long to wait between two trade status checks, when the
+/// status is polled. It lets integrators tune the load on the service against the latency.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PollStrategy {
+    /// Wait the same interval between two checks.
+    Fixed(Duration),
+    /// Start with `initial` and multiply the interval by `factor` after each check, up to `max`.
+    ///
+    /// The interval goes back to `initial` every time the status of the trade changes. It is
+    /// never shorter than `initial`, and a factor below 2 is used as 2.
+    /// Use `PollStrategy::exponential` to reject such values instead.
+    Exponential {
+        /// The interval after the first check.
+        initial: Duration,
+        /// The upper bound for the interval.
+        max: Duration,
+        /// The factor applied to the interval after each check, at least 2.
+        factor: u32,
+    },
+    /// Use a different interval depending on the last observed status of the trade,
+    /// e.g. fast while `Signed` and slower while `Confirmed`.
+    PhaseAware {
+        /// The interval to use for each status.
+        intervals: Vec<(TradeStatus, Duration)>,
+        /// The interval to use for the statuses not listed in `intervals`.
+        default: Duration,
+    },
+}
+
+impl Default for PollStrategy {
+    fn default() -> Self {
+        PollStrategy::Fixed(Duration::from_millis(500))
+    }
+}
+
+impl PollStrategy {
+    /// Creates an exponential strategy, see `PollStrategy::Exponential`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfig` if `factor` is below 2 or `initial` exceeds `max`.
+    pub fn exponential(initial: Duration, max: Duration, factor: u32) -> Result<Self> {
+        if factor < 2 {
+            return Err(Error::InvalidConfig(format!(
+                "The factor of an exponential poll strategy must be at least 2, got {}",
+                factor
+            )));
+        }
+        if initial > max {
+            return Err(Error::InvalidConfig(format!(
+                "The initial interval {:?} of an exponential poll strategy exceeds its maximum {:?}",
+                initial, max
+            )));
+        }
+        Ok(PollStrategy::Exponential {
+            initial,
+            max,
+            factor,
+        })
+    }
+
+    /// Computes the interval to wait before the next check.
+    ///
+    /// `checks` is the number of checks done since the status last changed (at least 1),
+    /// and `status` is the last observed status, if any.
+    pub fn interval(&self, checks: u32, status: Option<&TradeStatus>) -> Duration {
+        match self {
+            PollStrategy::Fixed(interval) => *interval,
+            PollStrategy::Exponential {
+                initial,
+                max,
+                factor,
+            } => initial
+                .saturating_mul((*factor).max(2).saturating_pow(checks.saturating_sub(1)))
+                .min(*max)
+                .max(*initial),
+            PollStrategy::PhaseAware { intervals, default } => status
+                .and_then(|status| {
+                    intervals
+                        .iter()
+                        .find(|(phase, _)| phase == status)
+                        .map(|(_, interval)| *interval)
+                })
+                .unwrap_or(*default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test exponential strategy
+    ///
+    /// This is used to test that the interval grows with the checks and is capped.
+    fn test_exponential_interval() {
+        let strategy = PollStrategy::Exponential {
+            initial: Duration::from_millis(200),
+            max: Duration::from_secs(1),
+            factor: 2,
+        };
+
+        assert_eq!(strategy.interval(1, None), Duration::from_millis(200));
+        assert_eq!(strategy.interval(2, None), Duration::from_millis(400));
+        assert_eq!(strategy.interval(3, None), Duration::from_millis(800));
+        assert_eq!(strategy.interval(4, None), Duration::from_secs(1));
+        assert_eq!(strategy.interval(100, None), Duration::from_secs(1));
+        assert_eq!(
+            PollStrategy::exponential(Duration::from_millis(200), Duration::from_secs(1), 2)
+                .unwrap(),
+            strategy
+        );
+    }
+
+    #[test]
+    /// Test invalid exponential strategy
+    ///
+    /// This is used to test that a factor below 2 or an initial interval above the maximum is
+    /// rejected by the constructor, and never polls faster than the initial interval otherwise.
+    fn test_invalid_exponential_strategy() {
+        let initial = Duration::from_millis(200);
+        for (max, factor) in [(Duration::from_secs(1), 0), (Duration::from_secs(1), 1)] {
+            assert!(matches!(
+                PollStrategy::exponential(initial, max, factor),
+                Err(Error::InvalidConfig(_))
+            ));
+        }
+        assert!(PollStrategy::exponential(initial, Duration::from_millis(100), 2).is_err());
+
+        let strategy = PollStrategy::Exponential {
+            initial,
+            max: Duration::from_secs(1),
+            factor: 0,
+        };
+        assert_eq!(strategy.interval(1, None), initial);
+        assert_eq!(strategy.interval(2, None), Duration::from_millis(400));
+        let strategy = PollStrategy::Exponential {
+            initial,
+            max: Duration::from_millis(100),
+            factor: 2,
+        };
+        assert_eq!(strategy.interval(3, None), initial);
+    }
+
+    #[test]
+    /// Test phase aware strategy
+    ///
+    /// This is used to test that the interval depends on the last observed status.
+    fn test_phase_aware_interval() {
+        let strategy = PollStrategy::PhaseAware {
+            intervals: vec![
+                (TradeStatus::Signed, Duration::from_millis(100)),
+                (TradeStatus::Confirmed, Duration::from_secs(2)),
+            ],
+            default: Duration::from_millis(500),
+        };
+
+        assert_eq!(
+            strategy.interval(1, Some(&TradeStatus::Signed)),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            strategy.interval(5, Some(&TradeStatus::Confirmed)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(strategy.interval(1, None), Duration::from_millis(500));
+    }
+}
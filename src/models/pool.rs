@@ -4,8 +4,18 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
-use crate::integrations_pb::{
-    InitPoolRequest as ProtoInitPoolRequest, InitPoolResponse as ProtoInitPoolResponse,
+use rand::distr::{Alphanumeric, SampleString};
+
+use crate::{
+    error::Result,
+    integrations_pb::{
+        InitPoolRequest as ProtoInitPoolRequest, InitPoolResponse as ProtoInitPoolResponse,
+    },
+    models::{
+        address::{Mint, WalletAddress},
+        enums::PriorityLevel,
+        validation::{ProtoRules, Validate, Validator},
+    },
 };
 
 /// Init pool request
@@ -14,11 +24,11 @@ use crate::integrations_pb::{
 #[derive(Debug, Clone)]
 pub struct InitPoolRequest {
     /// The mint address of the token X.
-    pub token_mint_x: String,
+    pub token_mint_x: Mint,
     /// The mint address of the token Y.
-    pub token_mint_y: String,
+    pub token_mint_y: Mint,
     /// The wallet address of the user.
-    pub user_address: String,
+    pub user_address: WalletAddress,
     /// The amount of token X to add.
     pub amount_x: u64,
     /// The amount of token Y to add.
@@ -27,6 +37,151 @@ pub struct InitPoolRequest {
     pub ref_code: String,
     /// The label to attach, 10 chars max.
     pub label: String,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    /// The tracking id for the call. This id helps in tracing the call on the server side, making it simple to debug issues.
+    pub tracking_id: String,
+}
+
+impl InitPoolRequest {
+    pub fn builder(
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
+        amount_x: u64,
+        amount_y: u64,
+    ) -> InitPoolRequestBuilder {
+        InitPoolRequestBuilder::new(user_address, token_mint_x, token_mint_y, amount_x, amount_y)
+    }
+}
+
+/// Validate implementation for the init pool request.
+///
+/// The rules of `api.proto` are checked first, then the mints must be distinct and a priority
+/// fee cannot be combined with a priority level.
+impl Validate for InitPoolRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoInitPoolRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .distinct_mints(&self.token_mint_x, &self.token_mint_y)
+            .priority(self.priority_fee_micro_lamports, self.priority_level)
+            .finish()
+    }
+}
+
+/// InitPoolRequestBuilder
+///
+/// This struct is used to build an InitPoolRequest.
+#[derive(Debug, Clone)]
+pub struct InitPoolRequestBuilder {
+    /// The mint address of the token X.
+    pub token_mint_x: Mint,
+    /// The mint address of the token Y.
+    pub token_mint_y: Mint,
+    /// The wallet address of the user.
+    pub user_address: WalletAddress,
+    /// The amount of token X to add.
+    pub amount_x: u64,
+    /// The amount of token Y to add.
+    pub amount_y: u64,
+    /// The referral code.
+    pub ref_code: Option<String>,
+    /// The label to attach, 10 chars max.
+    pub label: Option<String>,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    /// The tracking id for the call.
+    pub tracking_id: Option<String>,
+}
+
+impl InitPoolRequestBuilder {
+    pub fn new(
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
+        amount_x: u64,
+        amount_y: u64,
+    ) -> Self {
+        Self {
+            token_mint_x,
+            token_mint_y,
+            user_address,
+            amount_x,
+            amount_y,
+            ref_code: None,
+            label: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            priority_level: None,
+            tracking_id: None,
+        }
+    }
+
+    pub fn ref_code(mut self, ref_code: &str) -> Self {
+        self.ref_code = Some(ref_code.to_string());
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn tracking_id(mut self, tracking_id: &str) -> Self {
+        self.tracking_id = Some(tracking_id.to_string());
+        self
+    }
+
+    /// Sets the compute unit price, in micro lamports.
+    pub fn priority_fee_micro_lamports(mut self, priority_fee_micro_lamports: u64) -> Self {
+        self.priority_fee_micro_lamports = Some(priority_fee_micro_lamports);
+        self
+    }
+
+    /// Sets the compute unit limit.
+    pub fn compute_unit_limit(mut self, compute_unit_limit: u32) -> Self {
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self
+    }
+
+    /// Sets the priority level the service resolves into a compute unit price.
+    pub fn priority_level(mut self, priority_level: PriorityLevel) -> Self {
+        self.priority_level = Some(priority_level);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` with every offending field if the request is invalid.
+    pub fn build(self) -> Result<InitPoolRequest> {
+        let request = InitPoolRequest {
+            token_mint_x: self.token_mint_x,
+            token_mint_y: self.token_mint_y,
+            user_address: self.user_address,
+            amount_x: self.amount_x,
+            amount_y: self.amount_y,
+            ref_code: self.ref_code.unwrap_or_default(),
+            label: self.label.unwrap_or_default(),
+            priority_fee_micro_lamports: self.priority_fee_micro_lamports,
+            compute_unit_limit: self.compute_unit_limit,
+            priority_level: self.priority_level,
+            tracking_id: self
+                .tracking_id
+                .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12)),
+        };
+        request.validate()?;
+        Ok(request)
+    }
 }
 
 /// Init pool response
@@ -36,6 +191,12 @@ pub struct InitPoolRequest {
 pub struct InitPoolResponse {
     /// Base64 encoded unsigned transaction to send to the wallet for sign & execute.
     pub unsigned_transaction: String,
+    /// The compute unit price chosen by the service, in micro lamports.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit chosen by the service.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the compute unit price was resolved from.
+    pub priority_level: Option<PriorityLevel>,
 }
 
 /// Convert from ProtoInitPoolResponse to InitPoolResponse
@@ -45,6 +206,9 @@ impl From<ProtoInitPoolResponse> for InitPoolResponse {
     fn from(response: ProtoInitPoolResponse) -> Self {
         Self {
             unsigned_transaction: response.unsigned_transaction,
+            priority_fee_micro_lamports: response.priority_fee_micro_lamports,
+            compute_unit_limit: response.compute_unit_limit,
+            priority_level: PriorityLevel::from_proto(response.priority_level),
         }
     }
 }
@@ -55,13 +219,16 @@ impl From<ProtoInitPoolResponse> for InitPoolResponse {
 impl From<InitPoolRequest> for ProtoInitPoolRequest {
     fn from(request: InitPoolRequest) -> Self {
         Self {
-            token_mint_x: request.token_mint_x,
-            token_mint_y: request.token_mint_y,
-            user_address: request.user_address,
+            token_mint_x: request.token_mint_x.to_string(),
+            token_mint_y: request.token_mint_y.to_string(),
+            user_address: request.user_address.to_string(),
             amount_x: request.amount_x,
             amount_y: request.amount_y,
             ref_code: request.ref_code,
             label: request.label,
+            priority_fee_micro_lamports: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+            priority_level: PriorityLevel::to_proto(request.priority_level),
         }
     }
 }
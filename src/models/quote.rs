@@ -4,8 +4,19 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
-use crate::integrations_pb::{
-    QuoteRequest as ProtoQuoteRequest, QuoteResponse as ProtoQuoteResponse,
+use std::time::{Duration, Instant};
+
+use rand::distr::{Alphanumeric, SampleString};
+
+use crate::{
+    error::{Error, Result},
+    integrations_pb::{QuoteRequest as ProtoQuoteRequest, QuoteResponse as ProtoQuoteResponse},
+    models::{
+        address::Mint,
+        amount::TokenAmount,
+        trade::TokenMetadata,
+        validation::{ProtoRules, Validate, Validator},
+    },
 };
 
 /// Quote request
@@ -14,13 +25,91 @@ use crate::integrations_pb::{
 #[derive(Debug, Clone)]
 pub struct QuoteRequest {
     /// The mint address of the token X.
-    pub token_mint_x: String,
+    pub token_mint_x: Mint,
     /// The mint address of the token Y.
-    pub token_mint_y: String,
+    pub token_mint_y: Mint,
     /// The amount of token X to swap.
     pub amount_in: u64,
     /// Whether to swap token X to token Y.
     pub is_swap_x_to_y: bool,
+    /// The tracking id for the call. This id helps in tracing the call on the server side, making it simple to debug issues.
+    pub tracking_id: String,
+}
+
+impl QuoteRequest {
+    pub fn builder(token_mint_x: Mint, token_mint_y: Mint, amount_in: u64) -> QuoteRequestBuilder {
+        QuoteRequestBuilder::new(token_mint_x, token_mint_y, amount_in)
+    }
+}
+
+/// Validate implementation for the quote request.
+///
+/// The rules of `api.proto` are checked first, then the mints must be distinct.
+impl Validate for QuoteRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoQuoteRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .distinct_mints(&self.token_mint_x, &self.token_mint_y)
+            .finish()
+    }
+}
+
+/// QuoteRequestBuilder
+///
+/// This struct is used to build a QuoteRequest.
+#[derive(Debug, Clone)]
+pub struct QuoteRequestBuilder {
+    /// The mint address of the token X.
+    pub token_mint_x: Mint,
+    /// The mint address of the token Y.
+    pub token_mint_y: Mint,
+    /// The amount of token X to swap.
+    pub amount_in: u64,
+    /// Whether to swap token X to token Y.
+    pub is_swap_x_to_y: Option<bool>,
+    /// The tracking id for the call.
+    pub tracking_id: Option<String>,
+}
+
+impl QuoteRequestBuilder {
+    pub fn new(token_mint_x: Mint, token_mint_y: Mint, amount_in: u64) -> Self {
+        Self {
+            token_mint_x,
+            token_mint_y,
+            amount_in,
+            is_swap_x_to_y: None,
+            tracking_id: None,
+        }
+    }
+
+    pub fn is_swap_x_to_y(mut self, is_swap_x_to_y: bool) -> Self {
+        self.is_swap_x_to_y = Some(is_swap_x_to_y);
+        self
+    }
+
+    pub fn tracking_id(mut self, tracking_id: &str) -> Self {
+        self.tracking_id = Some(tracking_id.to_string());
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` with every offending field if the request is invalid.
+    pub fn build(self) -> Result<QuoteRequest> {
+        let request = QuoteRequest {
+            token_mint_x: self.token_mint_x,
+            token_mint_y: self.token_mint_y,
+            amount_in: self.amount_in,
+            is_swap_x_to_y: self.is_swap_x_to_y.unwrap_or(true),
+            tracking_id: self
+                .tracking_id
+                .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12)),
+        };
+        request.validate()?;
+        Ok(request)
+    }
 }
 
 /// Quote response
@@ -31,9 +120,9 @@ pub struct QuoteRequest {
 #[derive(Debug, Clone)]
 pub struct QuoteResponse {
     /// The mint address of the token X.
-    pub token_mint_x: String,
+    pub token_mint_x: Mint,
     /// The mint address of the token Y.
-    pub token_mint_y: String,
+    pub token_mint_y: Mint,
     /// The amount of token X to swap.
     pub amount_in: u64,
     /// The amount of token Y to receive.
@@ -44,23 +133,103 @@ pub struct QuoteResponse {
     pub fee_pct: f64,
     /// Whether to swap token X to token Y.
     pub is_swap_x_to_y: bool,
+    /// When the quote was received.
+    pub received_at: Instant,
 }
 
 /// Convert from ProtoQuoteResponse to QuoteResponse
 ///
 /// This function is used to convert from ProtoQuoteResponse to QuoteResponse.
-impl From<ProtoQuoteResponse> for QuoteResponse {
-    fn from(response: ProtoQuoteResponse) -> Self {
-        Self {
-            token_mint_x: response.token_mint_x,
-            token_mint_y: response.token_mint_y,
+/// It fails with `Error::InvalidAddress` if the service returns a malformed mint.
+impl TryFrom<ProtoQuoteResponse> for QuoteResponse {
+    type Error = Error;
+
+    fn try_from(response: ProtoQuoteResponse) -> Result<Self> {
+        Ok(Self {
+            token_mint_x: response.token_mint_x.parse()?,
+            token_mint_y: response.token_mint_y.parse()?,
             amount_in: response.amount_in,
             amount_out: response.amount_out,
             fee_amount: response.fee_amount,
             fee_pct: response.fee_pct,
             is_swap_x_to_y: response.is_swap_x_to_y,
+            received_at: Instant::now(),
+        })
+    }
+}
+
+impl QuoteResponse {
+    /// Returns the time elapsed since the quote was received.
+    pub fn age(&self) -> Duration {
+        self.received_at.elapsed()
+    }
+
+    /// Returns the mint of the token sold.
+    pub fn input_mint(&self) -> Mint {
+        if self.is_swap_x_to_y {
+            self.token_mint_x
+        } else {
+            self.token_mint_y
+        }
+    }
+
+    /// Returns the mint of the token bought.
+    pub fn output_mint(&self) -> Mint {
+        if self.is_swap_x_to_y {
+            self.token_mint_y
+        } else {
+            self.token_mint_x
         }
     }
+
+    /// Returns the amount sold, given the metadata of the input token.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidAmount` if the metadata is not the one of the input token.
+    pub fn input_amount(&self, token_in: &TokenMetadata) -> Result<TokenAmount> {
+        check_token(token_in, self.input_mint())?;
+        Ok(token_in.amount(self.amount_in))
+    }
+
+    /// Returns the amount bought, given the metadata of the output token.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidAmount` if the metadata is not the one of the output token.
+    pub fn output_amount(&self, token_out: &TokenMetadata) -> Result<TokenAmount> {
+        check_token(token_out, self.output_mint())?;
+        Ok(token_out.amount(self.amount_out))
+    }
+
+    /// Formats the quote in human units with the token symbols, e.g.
+    /// `1.5 SOL -> 210.3 USDC (fee 0.3%)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidAmount` if the metadata does not match the mints of the quote.
+    pub fn format(&self, token_in: &TokenMetadata, token_out: &TokenMetadata) -> Result<String> {
+        Ok(format!(
+            "{} {} -> {} {} (fee {}%)",
+            self.input_amount(token_in)?,
+            token_in.symbol,
+            self.output_amount(token_out)?,
+            token_out.symbol,
+            self.fee_pct
+        ))
+    }
+}
+
+/// Checks that the metadata is the one of the given mint.
+fn check_token(token: &TokenMetadata, mint: Mint) -> Result<()> {
+    if token.address == mint {
+        Ok(())
+    } else {
+        Err(Error::InvalidAmount(format!(
+            "{} is the metadata of {}, not {}",
+            token.symbol, token.address, mint
+        )))
+    }
 }
 
 /// Convert from QuoteRequest to ProtoQuoteRequest
@@ -69,10 +238,59 @@ impl From<ProtoQuoteResponse> for QuoteResponse {
 impl From<QuoteRequest> for ProtoQuoteRequest {
     fn from(request: QuoteRequest) -> Self {
         Self {
-            token_mint_x: request.token_mint_x,
-            token_mint_y: request.token_mint_y,
+            token_mint_x: request.token_mint_x.to_string(),
+            token_mint_y: request.token_mint_y.to_string(),
             amount_in: request.amount_in,
             is_swap_x_to_y: request.is_swap_x_to_y,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(symbol: &str, decimals: u8, address: &str) -> TokenMetadata {
+        TokenMetadata {
+            name: symbol.to_string(),
+            symbol: symbol.to_string(),
+            decimals,
+            logo_uri: String::new(),
+            address: address.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    /// Test quote formatting
+    ///
+    /// This is used to test that a quote is rendered in human units, in the swap direction, and
+    /// that the metadata of the wrong token is rejected.
+    fn test_format_quote() {
+        let sol = token("SOL", 9, "So11111111111111111111111111111111111111112");
+        let usdc = token("USDC", 6, "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        let quote = QuoteResponse {
+            token_mint_x: usdc.address,
+            token_mint_y: sol.address,
+            amount_in: 1_500_000_000,
+            amount_out: 210_300_000,
+            fee_amount: 0,
+            fee_pct: 0.3,
+            is_swap_x_to_y: false,
+            received_at: Instant::now(),
+        };
+
+        assert_eq!(
+            quote.format(&sol, &usdc).unwrap(),
+            "1.5 SOL -> 210.3 USDC (fee 0.3%)"
+        );
+        assert!(matches!(
+            quote.format(&usdc, &sol),
+            Err(Error::InvalidAmount(_))
+        ));
+        assert_eq!(
+            sol.parse_amount("1.5 sol").unwrap(),
+            TokenAmount::new(1_500_000_000, 9)
+        );
+        assert!(sol.parse_amount("1.5 USDC").is_err());
+    }
+}
@@ -5,14 +5,18 @@
 // https://opensource.org/licenses/MIT.
 
 use crate::{
-    CheckTradeStatusRequest, TradeStatus,
+    CheckTradeStatusRequest, PollStrategy, TradeStatus,
+    error::Result,
     integrations_pb::{
         SendSignedTransactionRequest as ProtoSendSignedTransactionRequest,
         SendSignedTransactionResponse as ProtoSendSignedTransactionResponse,
     },
+    models::validation::{ProtoRules, Validate, Validator},
 };
 use rand::distr::{Alphanumeric, SampleString};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 /// SendSignedTransactionRequest
 ///
@@ -36,6 +40,17 @@ impl SendSignedTransactionRequest {
     }
 }
 
+/// Validate implementation for the signed transaction request.
+///
+/// The signed transaction and the trade id must not be empty, as declared in `api.proto`.
+impl Validate for SendSignedTransactionRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoSendSignedTransactionRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .finish()
+    }
+}
+
 /// SendSignedTransactionRequestBuilder
 ///
 /// This struct is used to build a SendSignedTransactionRequest.
@@ -64,15 +79,12 @@ impl SendSignedTransactionRequestBuilder {
     }
 
     pub fn build(self) -> SendSignedTransactionRequest {
-        let tracking_id: String;
-        if self.tracking_id.is_none() {
-            tracking_id = Alphanumeric.sample_string(&mut rand::rng(), 12);
-        } else {
-            tracking_id = self.tracking_id.unwrap();
-        }
+        let tracking_id = self
+            .tracking_id
+            .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12));
         SendSignedTransactionRequest {
             signed_transaction: self.signed_transaction,
-            tracking_id: tracking_id,
+            tracking_id,
             trade_id: self.trade_id,
         }
     }
@@ -104,6 +116,29 @@ pub struct SendSignedTransactionResponse {
     pub error_logs: Vec<String>,
 }
 
+/// The fragments of the error logs reporting an expired blockhash, in lowercase.
+const BLOCKHASH_EXPIRED_LOGS: [&str; 4] = [
+    "blockhash not found",
+    "blockhashnotfound",
+    "block height exceeded",
+    "blockheightexceeded",
+];
+
+impl SendSignedTransactionResponse {
+    /// Whether the transaction failed because its recent blockhash expired before it was sent,
+    /// e.g. because the signing took too long. The trade should then be re-created with
+    /// `Client::refresh_unsigned_transaction` and signed again.
+    pub fn is_blockhash_expired(&self) -> bool {
+        !self.success
+            && self.error_logs.iter().any(|log| {
+                let log = log.to_lowercase();
+                BLOCKHASH_EXPIRED_LOGS
+                    .iter()
+                    .any(|fragment| log.contains(fragment))
+            })
+    }
+}
+
 /// Convert from ProtoSendSignedTransactionResponse to SendSignedTransactionResponse
 ///
 /// This function is used to convert from ProtoSendSignedTransactionResponse to SendSignedTransactionResponse.
@@ -128,27 +163,29 @@ pub struct SendSignedTransactionAndCheckStatusRequest {
     pub tracking_id: String,
     /// The trade id.
     pub trade_id: String,
-    /// The channel to send the send signed transaction response to.
-    pub tx_response: mpsc::Sender<SendSignedTransactionResponse>,
-    /// The channel to send the trade status to.
+    /// The optional channel to send the send signed transaction response to.
+    pub tx_response: Option<mpsc::Sender<SendSignedTransactionResponse>>,
+    /// The optional channel to send the trade status to.
     pub tx_status: Option<mpsc::Sender<TradeStatus>>,
     /// The interval in milliseconds to check the trade status.
     pub interval_millis: Option<u64>,
-    /// The maximum number of attempts to check the trade status.
+    /// The maximum number of attempts to check the trade status when it is polled. It does not
+    /// apply when the server streams the status.
     pub max_attempts: Option<u32>,
+    /// The deadline after which the trade status is not checked anymore.
+    pub deadline: Option<Instant>,
+    /// The token used to stop checking the trade status before the trade completes.
+    pub cancellation_token: Option<CancellationToken>,
+    /// The strategy used to poll the trade status. It takes precedence over `interval_millis`.
+    pub poll_strategy: Option<PollStrategy>,
 }
 
 impl SendSignedTransactionAndCheckStatusRequest {
     pub fn builder(
         signed_transaction: &str,
         trade_id: &str,
-        tx_response: mpsc::Sender<SendSignedTransactionResponse>,
     ) -> SendSignedTransactionAndCheckStatusRequestBuilder {
-        SendSignedTransactionAndCheckStatusRequestBuilder::new(
-            signed_transaction,
-            trade_id,
-            tx_response,
-        )
+        SendSignedTransactionAndCheckStatusRequestBuilder::new(signed_transaction, trade_id)
     }
 }
 
@@ -173,6 +210,9 @@ impl From<SendSignedTransactionAndCheckStatusRequest> for CheckTradeStatusReques
         Self {
             tracking_id: request.tracking_id,
             trade_id: request.trade_id,
+            deadline: request.deadline,
+            cancellation_token: request.cancellation_token,
+            poll_strategy: request.poll_strategy,
         }
     }
 }
@@ -188,33 +228,39 @@ pub struct SendSignedTransactionAndCheckStatusRequestBuilder {
     pub tracking_id: Option<String>,
     /// The trade id.
     pub trade_id: String,
-    /// The channel to send the send signed transaction response to.
-    pub tx_response: mpsc::Sender<SendSignedTransactionResponse>,
-    /// The channel to send the trade status to.
+    /// The optional channel to send the send signed transaction response to.
+    pub tx_response: Option<mpsc::Sender<SendSignedTransactionResponse>>,
+    /// The optional channel to send the trade status to.
     pub tx_status: Option<mpsc::Sender<TradeStatus>>,
     /// The interval in milliseconds to check the trade status.
     pub interval_millis: Option<u64>,
-    /// The maximum number of attempts to check the trade status.
+    /// The maximum number of attempts to check the trade status when it is polled. It does not
+    /// apply when the server streams the status.
     pub max_attempts: Option<u32>,
+    /// The deadline after which the trade status is not checked anymore.
+    pub deadline: Option<Instant>,
+    /// The token used to stop checking the trade status before the trade completes.
+    pub cancellation_token: Option<CancellationToken>,
+    /// The strategy used to poll the trade status. It takes precedence over `interval_millis`.
+    pub poll_strategy: Option<PollStrategy>,
 }
 
 /// SendSignedTransactionAndCheckStatusRequestBuilder
 ///
 /// This struct is used to build a SendSignedTransactionAndCheckStatusRequest.
 impl SendSignedTransactionAndCheckStatusRequestBuilder {
-    pub fn new(
-        signed_transaction: &str,
-        trade_id: &str,
-        tx_response: mpsc::Sender<SendSignedTransactionResponse>,
-    ) -> Self {
+    pub fn new(signed_transaction: &str, trade_id: &str) -> Self {
         Self {
             signed_transaction: signed_transaction.to_string(),
             tracking_id: None,
             trade_id: trade_id.to_string(),
-            tx_response: tx_response,
+            tx_response: None,
             tx_status: None,
             interval_millis: None,
             max_attempts: None,
+            deadline: None,
+            cancellation_token: None,
+            poll_strategy: None,
         }
     }
 
@@ -223,6 +269,11 @@ impl SendSignedTransactionAndCheckStatusRequestBuilder {
         self
     }
 
+    pub fn tx_response(mut self, tx_response: mpsc::Sender<SendSignedTransactionResponse>) -> Self {
+        self.tx_response = Some(tx_response);
+        self
+    }
+
     pub fn tx_status(mut self, tx_status: mpsc::Sender<TradeStatus>) -> Self {
         self.tx_status = Some(tx_status);
         self
@@ -238,21 +289,42 @@ impl SendSignedTransactionAndCheckStatusRequestBuilder {
         self
     }
 
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the deadline to `timeout` from now.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    pub fn poll_strategy(mut self, poll_strategy: PollStrategy) -> Self {
+        self.poll_strategy = Some(poll_strategy);
+        self
+    }
+
     pub fn build(self) -> SendSignedTransactionAndCheckStatusRequest {
-        let tracking_id: String;
-        if self.tracking_id.is_none() {
-            tracking_id = Alphanumeric.sample_string(&mut rand::rng(), 12);
-        } else {
-            tracking_id = self.tracking_id.unwrap();
-        }
+        let tracking_id = self
+            .tracking_id
+            .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12));
         SendSignedTransactionAndCheckStatusRequest {
             signed_transaction: self.signed_transaction,
-            tracking_id: tracking_id,
+            tracking_id,
             trade_id: self.trade_id,
             tx_response: self.tx_response,
             tx_status: self.tx_status,
             interval_millis: self.interval_millis,
             max_attempts: self.max_attempts,
+            deadline: self.deadline,
+            cancellation_token: self.cancellation_token,
+            poll_strategy: self.poll_strategy,
         }
     }
 }
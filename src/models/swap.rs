@@ -0,0 +1,224 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::time::{Duration, Instant};
+
+use rand::distr::{Alphanumeric, SampleString};
+use tokio_util::sync::CancellationToken;
+
+#[cfg(feature = "solana")]
+use crate::core::verify::VerificationPolicy;
+use crate::models::{
+    CheckTradeStatusResponse, CreateUnsignedTransactionRequest, CreateUnsignedTransactionResponse,
+    PhaseTimings, PollStrategy, PriorityLevel, QuoteRequest, QuoteResponse,
+    SendSignedTransactionResponse, StatusTransition, WalletAddress,
+};
+
+/// Swap params
+///
+/// This struct is used to define a swap run end to end by `Client::swap`.
+#[derive(Debug, Clone)]
+pub struct SwapParams {
+    /// The quote request of the swap.
+    pub quote_request: QuoteRequest,
+    /// The wallet address of the user, which signs the transaction.
+    pub user_address: WalletAddress,
+    /// The slippage tolerance, in basis points of the quoted output.
+    pub slippage_bps: u16,
+    /// The tracking id used for every call of the swap.
+    pub tracking_id: String,
+    /// The deadline after which the trade status is not checked anymore.
+    pub deadline: Option<Instant>,
+    /// The token used to stop checking the trade status before the trade completes.
+    pub cancellation_token: Option<CancellationToken>,
+    /// The strategy used to poll the trade status.
+    pub poll_strategy: Option<PollStrategy>,
+    /// The compute unit price of the transaction, in micro lamports.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit of the transaction.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    /// How the unsigned transaction is verified before it is signed.
+    pub(crate) verification: Verification,
+}
+
+/// How `Client::swap` verifies the unsigned transaction before it is signed.
+#[derive(Debug, Clone)]
+pub(crate) enum Verification {
+    /// Neither a policy nor `skip_verification` was set, the swap is refused.
+    Unset,
+    /// The transaction is signed without verification.
+    Skipped,
+    /// The transaction is verified against the policy.
+    #[cfg(feature = "solana")]
+    Policy(VerificationPolicy),
+}
+
+impl SwapParams {
+    pub fn builder(
+        quote_request: QuoteRequest,
+        user_address: WalletAddress,
+        slippage_bps: u16,
+    ) -> SwapParamsBuilder {
+        SwapParamsBuilder::new(quote_request, user_address, slippage_bps)
+    }
+}
+
+/// SwapParamsBuilder
+///
+/// This struct is used to build a SwapParams.
+#[derive(Debug, Clone)]
+pub struct SwapParamsBuilder {
+    /// The quote request of the swap.
+    pub quote_request: QuoteRequest,
+    /// The wallet address of the user, which signs the transaction.
+    pub user_address: WalletAddress,
+    /// The slippage tolerance, in basis points of the quoted output.
+    pub slippage_bps: u16,
+    /// The tracking id used for every call of the swap.
+    pub tracking_id: Option<String>,
+    /// The deadline after which the trade status is not checked anymore.
+    pub deadline: Option<Instant>,
+    /// The token used to stop checking the trade status before the trade completes.
+    pub cancellation_token: Option<CancellationToken>,
+    /// The strategy used to poll the trade status.
+    pub poll_strategy: Option<PollStrategy>,
+    /// The compute unit price of the transaction, in micro lamports.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit of the transaction.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
+    verification: Verification,
+}
+
+impl SwapParamsBuilder {
+    pub fn new(
+        quote_request: QuoteRequest,
+        user_address: WalletAddress,
+        slippage_bps: u16,
+    ) -> Self {
+        Self {
+            quote_request,
+            user_address,
+            slippage_bps,
+            tracking_id: None,
+            deadline: None,
+            cancellation_token: None,
+            poll_strategy: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            priority_level: None,
+            verification: Verification::Unset,
+        }
+    }
+
+    pub fn tracking_id(mut self, tracking_id: &str) -> Self {
+        self.tracking_id = Some(tracking_id.to_string());
+        self
+    }
+
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the deadline to `timeout` from now.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    pub fn poll_strategy(mut self, poll_strategy: PollStrategy) -> Self {
+        self.poll_strategy = Some(poll_strategy);
+        self
+    }
+
+    /// Sets the compute unit price of the transaction, in micro lamports.
+    pub fn priority_fee_micro_lamports(mut self, priority_fee_micro_lamports: u64) -> Self {
+        self.priority_fee_micro_lamports = Some(priority_fee_micro_lamports);
+        self
+    }
+
+    /// Sets the compute unit limit of the transaction.
+    pub fn compute_unit_limit(mut self, compute_unit_limit: u32) -> Self {
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self
+    }
+
+    /// Sets the priority level the service resolves into a compute unit price.
+    pub fn priority_level(mut self, priority_level: PriorityLevel) -> Self {
+        self.priority_level = Some(priority_level);
+        self
+    }
+
+    /// Sets the policy the unsigned transaction is verified against. The swap fails with
+    /// `Error::Verification`, before anything is signed, if the transaction does not match.
+    #[cfg(feature = "solana")]
+    pub fn verification_policy(mut self, verification_policy: VerificationPolicy) -> Self {
+        self.verification = Verification::Policy(verification_policy);
+        self
+    }
+
+    /// Signs the unsigned transaction without verifying it.
+    ///
+    /// **Warning:** the signer then approves whatever the service returns, including
+    /// instructions moving more funds than requested. Only use it when the signer checks the
+    /// transaction itself, or in tests. Without it or a verification policy, the swap is refused.
+    pub fn skip_verification(mut self) -> Self {
+        self.verification = Verification::Skipped;
+        self
+    }
+
+    pub fn build(self) -> SwapParams {
+        let tracking_id = self
+            .tracking_id
+            .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12));
+        SwapParams {
+            quote_request: self.quote_request,
+            user_address: self.user_address,
+            slippage_bps: self.slippage_bps,
+            tracking_id,
+            deadline: self.deadline,
+            cancellation_token: self.cancellation_token,
+            poll_strategy: self.poll_strategy,
+            priority_fee_micro_lamports: self.priority_fee_micro_lamports,
+            compute_unit_limit: self.compute_unit_limit,
+            priority_level: self.priority_level,
+            verification: self.verification,
+        }
+    }
+}
+
+/// Swap result
+///
+/// This struct holds every artifact of a swap run by `Client::swap`, from the quote to the
+/// terminal status of the trade.
+#[derive(Debug, Clone)]
+pub struct SwapResult {
+    /// The quote the swap was built from.
+    pub quote: QuoteResponse,
+    /// The request sent to create the unsigned transaction.
+    pub request: CreateUnsignedTransactionRequest,
+    /// The unsigned transaction, with the order and trade ids.
+    pub unsigned: CreateUnsignedTransactionResponse,
+    /// The base64 encoded signed transaction.
+    pub signed_transaction: String,
+    /// The response to the submission of the signed transaction.
+    pub submitted: SendSignedTransactionResponse,
+    /// The status transitions observed until the trade completed, including anomalies.
+    pub transitions: Vec<StatusTransition>,
+    /// The terminal status of the trade.
+    pub terminal: CheckTradeStatusResponse,
+    /// The time spent in each status before reaching the terminal one.
+    pub timings: PhaseTimings,
+}
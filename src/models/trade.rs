@@ -4,9 +4,16 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
 use rand::distr::{Alphanumeric, SampleString};
+use tokio_util::sync::CancellationToken;
 
 use crate::integrations_pb::{
+    CheckTradeStatusBatchRequest as ProtoCheckTradeStatusBatchRequest,
     CheckTradeStatusRequest as ProtoCheckTradeStatusRequest,
     CheckTradeStatusResponse as ProtoCheckTradeStatusResponse,
     GetTradesListByUserRequest as ProtoGetTradesListByUserRequest,
@@ -14,7 +21,16 @@ use crate::integrations_pb::{
     TokenMetadata as ProtoTokenMetadata, Trade as ProtoTrade,
 };
 
-use crate::models::enums::TradeStatus;
+use crate::{
+    error::{Error, Result},
+    models::{
+        address::{Mint, WalletAddress},
+        amount::TokenAmount,
+        enums::TradeStatus,
+        poll::PollStrategy,
+        validation::{ProtoRules, Validate, Validator},
+    },
+};
 
 /// CheckTradeStatusRequest
 ///
@@ -25,6 +41,12 @@ pub struct CheckTradeStatusRequest {
     pub tracking_id: String,
     /// The trade id.
     pub trade_id: String,
+    /// The deadline after which the watchers stop waiting for the trade to complete.
+    pub deadline: Option<Instant>,
+    /// The token used to stop the watchers before the trade completes.
+    pub cancellation_token: Option<CancellationToken>,
+    /// The strategy used by the watchers when the status is polled.
+    pub poll_strategy: Option<PollStrategy>,
 }
 
 impl CheckTradeStatusRequest {
@@ -33,6 +55,17 @@ impl CheckTradeStatusRequest {
     }
 }
 
+/// Validate implementation for the check trade status request.
+///
+/// The trade id must not be empty, as declared in `api.proto`.
+impl Validate for CheckTradeStatusRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoCheckTradeStatusRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .finish()
+    }
+}
+
 /// CheckTradeStatusRequestBuilder
 ///
 /// This struct is used to build a CheckTradeStatusRequest.
@@ -42,6 +75,12 @@ pub struct CheckTradeStatusRequestBuilder {
     pub tracking_id: Option<String>,
     /// The trade id.
     pub trade_id: String,
+    /// The deadline after which the watchers stop waiting for the trade to complete.
+    pub deadline: Option<Instant>,
+    /// The token used to stop the watchers before the trade completes.
+    pub cancellation_token: Option<CancellationToken>,
+    /// The strategy used by the watchers when the status is polled.
+    pub poll_strategy: Option<PollStrategy>,
 }
 
 impl CheckTradeStatusRequestBuilder {
@@ -49,6 +88,9 @@ impl CheckTradeStatusRequestBuilder {
         Self {
             tracking_id: None,
             trade_id: trade_id.to_string(),
+            deadline: None,
+            cancellation_token: None,
+            poll_strategy: None,
         }
     }
 
@@ -57,16 +99,37 @@ impl CheckTradeStatusRequestBuilder {
         self
     }
 
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the deadline to `timeout` from now.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    pub fn poll_strategy(mut self, poll_strategy: PollStrategy) -> Self {
+        self.poll_strategy = Some(poll_strategy);
+        self
+    }
+
     pub fn build(self) -> CheckTradeStatusRequest {
-        let tracking_id: String;
-        if self.tracking_id.is_none() {
-            tracking_id = Alphanumeric.sample_string(&mut rand::rng(), 12);
-        } else {
-            tracking_id = self.tracking_id.unwrap();
-        }
+        let tracking_id = self
+            .tracking_id
+            .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12));
         CheckTradeStatusRequest {
-            tracking_id: tracking_id,
+            tracking_id,
             trade_id: self.trade_id,
+            deadline: self.deadline,
+            cancellation_token: self.cancellation_token,
+            poll_strategy: self.poll_strategy,
         }
     }
 }
@@ -106,17 +169,84 @@ impl From<ProtoCheckTradeStatusResponse> for CheckTradeStatusResponse {
     }
 }
 
+/// CheckTradeStatusBatchRequest
+///
+/// This struct is used to check the status of several trades in one call.
+#[derive(Debug, Clone)]
+pub struct CheckTradeStatusBatchRequest {
+    /// The tracking id for the call. This id helps in tracing the call on the server side, making it simple to debug issues.
+    pub tracking_id: String,
+    /// The trade ids.
+    pub trade_ids: Vec<String>,
+}
+
+impl CheckTradeStatusBatchRequest {
+    pub fn new(trade_ids: &[&str]) -> Self {
+        Self {
+            tracking_id: Alphanumeric.sample_string(&mut rand::rng(), 12),
+            trade_ids: trade_ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+}
+
+/// Validate implementation for the trade status batch request.
+///
+/// The number of trade ids must not exceed the limit declared in `api.proto`.
+impl Validate for CheckTradeStatusBatchRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoCheckTradeStatusBatchRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .finish()
+    }
+}
+
+/// Convert from CheckTradeStatusBatchRequest to ProtoCheckTradeStatusBatchRequest
+///
+/// This function is used to convert from CheckTradeStatusBatchRequest to ProtoCheckTradeStatusBatchRequest.
+impl From<CheckTradeStatusBatchRequest> for ProtoCheckTradeStatusBatchRequest {
+    fn from(request: CheckTradeStatusBatchRequest) -> Self {
+        Self {
+            tracking_id: request.tracking_id,
+            trade_ids: request.trade_ids,
+        }
+    }
+}
+
 /// GetTradesListByUserRequest
 ///
 /// This struct is used to get the list of trades for a user.
 #[derive(Debug, Clone)]
 pub struct GetTradesListByUserRequest {
     /// The wallet address of the user.
-    pub user_address: String,
+    pub user_address: WalletAddress,
     /// The page size.
     pub page_size: i32,
     /// The page number.
     pub page_number: i32,
+    /// The tracking id for the call. This id helps in tracing the call on the server side, making it simple to debug issues.
+    pub tracking_id: String,
+}
+
+impl GetTradesListByUserRequest {
+    pub fn new(user_address: WalletAddress, page_size: i32, page_number: i32) -> Self {
+        Self {
+            user_address,
+            page_size,
+            page_number,
+            tracking_id: Alphanumeric.sample_string(&mut rand::rng(), 12),
+        }
+    }
+}
+
+/// Validate implementation for the trades list request.
+///
+/// The page size and page number must be in the ranges declared in `api.proto`.
+impl Validate for GetTradesListByUserRequest {
+    fn validate(&self) -> Result<()> {
+        ProtoGetTradesListByUserRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .finish()
+    }
 }
 
 /// GetTradesListByUserResponse
@@ -138,7 +268,7 @@ pub struct GetTradesListByUserResponse {
 impl From<GetTradesListByUserRequest> for ProtoGetTradesListByUserRequest {
     fn from(request: GetTradesListByUserRequest) -> Self {
         Self {
-            user_address: request.user_address,
+            user_address: request.user_address.to_string(),
             page_size: request.page_size,
             page_number: request.page_number,
         }
@@ -148,17 +278,20 @@ impl From<GetTradesListByUserRequest> for ProtoGetTradesListByUserRequest {
 /// Convert from ProtoGetTradesListByUserResponse to GetTradesListByUserResponse
 ///
 /// This function is used to convert from ProtoGetTradesListByUserResponse to GetTradesListByUserResponse.
-impl From<ProtoGetTradesListByUserResponse> for GetTradesListByUserResponse {
-    fn from(response: ProtoGetTradesListByUserResponse) -> Self {
-        Self {
+/// It fails with `Error::InvalidAddress` if the service returns a malformed address.
+impl TryFrom<ProtoGetTradesListByUserResponse> for GetTradesListByUserResponse {
+    type Error = Error;
+
+    fn try_from(response: ProtoGetTradesListByUserResponse) -> Result<Self> {
+        Ok(Self {
             trades: response
                 .trades
                 .into_iter()
-                .map(|trade| trade.into())
-                .collect(),
+                .map(Trade::try_from)
+                .collect::<Result<_>>()?,
             total_pages: response.total_pages,
             current_page: response.current_page,
-        }
+        })
     }
 }
 
@@ -172,7 +305,7 @@ pub struct Trade {
     /// The order id.
     pub order_id: String,
     /// The user address.
-    pub user_address: String,
+    pub user_address: WalletAddress,
     /// The token X.
     pub token_x: Option<TokenMetadata>,
     /// The token Y.
@@ -196,14 +329,18 @@ pub struct Trade {
 /// Convert from ProtoTrade to Trade
 ///
 /// This function is used to convert from ProtoTrade to Trade.
-impl From<ProtoTrade> for Trade {
-    fn from(trade: ProtoTrade) -> Self {
-        Self {
+/// It fails with `Error::InvalidAddress` if the trade holds a malformed address, or
+/// `Error::InvalidAmount` if a token has more decimals than supported.
+impl TryFrom<ProtoTrade> for Trade {
+    type Error = Error;
+
+    fn try_from(trade: ProtoTrade) -> Result<Self> {
+        Ok(Self {
             trade_id: trade.trade_id,
             order_id: trade.order_id,
-            user_address: trade.user_address,
-            token_x: trade.token_x.map(|token| token.into()),
-            token_y: trade.token_y.map(|token| token.into()),
+            user_address: trade.user_address.parse()?,
+            token_x: trade.token_x.map(TokenMetadata::try_from).transpose()?,
+            token_y: trade.token_y.map(TokenMetadata::try_from).transpose()?,
             amount_in: trade.amount_in,
             minimal_amount_out: trade.minimal_amount_out,
             status: trade.status.into(),
@@ -211,7 +348,7 @@ impl From<ProtoTrade> for Trade {
             created_at: trade.created_at,
             updated_at: trade.updated_at,
             is_swap_x_to_y: trade.is_swap_x_to_y,
-        }
+        })
     }
 }
 
@@ -225,24 +362,176 @@ pub struct TokenMetadata {
     /// The symbol.
     pub symbol: String,
     /// The decimals.
-    pub decimals: u32,
+    pub decimals: u8,
     /// The logo URI.
     pub logo_uri: String,
     /// The address.
-    pub address: String,
+    pub address: Mint,
 }
 
 /// Convert from ProtoTokenMetadata to TokenMetadata
 ///
 /// This function is used to convert from ProtoTokenMetadata to TokenMetadata.
-impl From<ProtoTokenMetadata> for TokenMetadata {
-    fn from(token: ProtoTokenMetadata) -> Self {
-        Self {
+/// It fails with `Error::InvalidAddress` if the mint address is malformed.
+impl TryFrom<ProtoTokenMetadata> for TokenMetadata {
+    type Error = Error;
+
+    fn try_from(token: ProtoTokenMetadata) -> Result<Self> {
+        let decimals = u8::try_from(token.decimals).map_err(|_| {
+            Error::InvalidAmount(format!(
+                "{} has {} decimals, at most {} are supported",
+                token.symbol,
+                token.decimals,
+                u8::MAX
+            ))
+        })?;
+        Ok(Self {
             name: token.name,
             symbol: token.symbol,
-            decimals: token.decimals,
+            decimals,
             logo_uri: token.logo_uri,
-            address: token.address,
+            address: token.address.parse()?,
+        })
+    }
+}
+
+impl TokenMetadata {
+    /// Returns the amount of `raw` units of this token.
+    pub fn amount(&self, raw: u64) -> TokenAmount {
+        TokenAmount::new(raw, self.decimals)
+    }
+
+    /// Formats `raw` units of this token in human units with the symbol, e.g. `1.5 SOL`.
+    pub fn format_amount(&self, raw: u64) -> String {
+        format!("{} {}", self.amount(raw), self.symbol)
+    }
+
+    /// Parses an amount of this token in human units, e.g. `1.5` or `1.5 SOL`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidAmount` if the amount is invalid or the symbol is not the one of
+    /// this token.
+    pub fn parse_amount(&self, value: &str) -> Result<TokenAmount> {
+        let amount = match value.trim().split_once(char::is_whitespace) {
+            Some((amount, symbol)) if symbol.trim().eq_ignore_ascii_case(&self.symbol) => amount,
+            Some((_, symbol)) => {
+                return Err(Error::InvalidAmount(format!(
+                    "{}: expected an amount of {}, not {}",
+                    value,
+                    self.symbol,
+                    symbol.trim()
+                )));
+            }
+            None => value,
+        };
+        TokenAmount::parse(amount, self.decimals)
+    }
+}
+
+impl Trade {
+    /// Returns the metadata of the token sold by the trade, if known.
+    pub fn input_token(&self) -> Option<&TokenMetadata> {
+        if self.is_swap_x_to_y {
+            self.token_x.as_ref()
+        } else {
+            self.token_y.as_ref()
         }
     }
+
+    /// Returns the metadata of the token bought by the trade, if known.
+    pub fn output_token(&self) -> Option<&TokenMetadata> {
+        if self.is_swap_x_to_y {
+            self.token_y.as_ref()
+        } else {
+            self.token_x.as_ref()
+        }
+    }
+
+    /// Returns the amount sold, if the metadata of the input token is known.
+    pub fn input_amount(&self) -> Option<TokenAmount> {
+        Some(self.input_token()?.amount(self.amount_in))
+    }
+
+    /// Returns the minimal amount bought, if the metadata of the output token is known.
+    pub fn minimal_output_amount(&self) -> Option<TokenAmount> {
+        Some(self.output_token()?.amount(self.minimal_amount_out))
+    }
+}
+
+/// Display implementation for the trade.
+///
+/// This implementation provides the amounts in human units with the token symbols, e.g.
+/// `abc: 1.5 SOL -> at least 210.3 USDC (Settled)`. Raw amounts are shown when the token
+/// metadata is missing.
+impl fmt::Display for Trade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = |token: Option<&TokenMetadata>, raw: u64| match token {
+            Some(token) => token.format_amount(raw),
+            None => raw.to_string(),
+        };
+        write!(
+            f,
+            "{}: {} -> at least {} ({:?})",
+            self.trade_id,
+            format(self.input_token(), self.amount_in),
+            format(self.output_token(), self.minimal_amount_out),
+            self.status
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test trades list conversion
+    ///
+    /// This is used to test that a trade with a malformed address fails the conversion of the
+    /// page instead of being dropped silently.
+    fn test_trades_list_rejects_invalid_trades() {
+        let trade = |trade_id: &str, user_address: &str| ProtoTrade {
+            trade_id: trade_id.to_string(),
+            user_address: user_address.to_string(),
+            ..Default::default()
+        };
+        let response = ProtoGetTradesListByUserResponse {
+            trades: vec![
+                trade("invalid", "not an address"),
+                trade("valid", "So11111111111111111111111111111111111111112"),
+            ],
+            total_pages: 1,
+            current_page: 0,
+        };
+
+        let result = GetTradesListByUserResponse::try_from(response);
+
+        assert!(matches!(result, Err(Error::InvalidAddress(_))));
+    }
+
+    #[test]
+    /// Test trade conversion with invalid token
+    ///
+    /// This is used to test that a token with too many decimals fails the conversion of the
+    /// trade instead of being hidden.
+    fn test_trade_rejects_invalid_token() {
+        let token = |decimals| ProtoTokenMetadata {
+            symbol: "SOL".to_string(),
+            decimals,
+            address: "So11111111111111111111111111111111111111112".to_string(),
+            ..Default::default()
+        };
+        let trade = ProtoTrade {
+            trade_id: "trade".to_string(),
+            user_address: "So11111111111111111111111111111111111111112".to_string(),
+            token_x: Some(token(9)),
+            token_y: Some(token(256)),
+            ..Default::default()
+        };
+
+        let result = Trade::try_from(trade);
+
+        assert!(matches!(result, Err(Error::InvalidAmount(_))));
+    }
 }
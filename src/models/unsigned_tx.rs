@@ -4,11 +4,22 @@
 // license that can be found in the LICENSE file or at
 // https://opensource.org/licenses/MIT.
 
+use std::time::{Duration, Instant};
+
 use rand::distr::{Alphanumeric, SampleString};
 
-use crate::integrations_pb::{
-    CreateUnsignedTransactionRequest as ProtoCreateUnsignedTransactionRequest,
-    CreateUnsignedTransactionResponse as ProtoCreateUnsignedTransactionResponse,
+use crate::{
+    error::{Error, Result},
+    integrations_pb::{
+        CreateUnsignedTransactionRequest as ProtoCreateUnsignedTransactionRequest,
+        CreateUnsignedTransactionResponse as ProtoCreateUnsignedTransactionResponse,
+    },
+    models::{
+        address::{Mint, WalletAddress},
+        enums::PriorityLevel,
+        quote::QuoteResponse,
+        validation::{ProtoRules, Validate, Validator},
+    },
 };
 
 /// CreateUnsignedTransactionRequest
@@ -17,11 +28,11 @@ use crate::integrations_pb::{
 #[derive(Debug, Clone)]
 pub struct CreateUnsignedTransactionRequest {
     /// The wallet address of the user.
-    pub user_address: String,
+    pub user_address: WalletAddress,
     /// The mint address of the token X.
-    pub token_mint_x: String,
+    pub token_mint_x: Mint,
     /// The mint address of the token Y.
-    pub token_mint_y: String,
+    pub token_mint_y: Mint,
     /// The amount of token to swap.
     pub amount_in: u64,
     /// The minimum amount of token to receive.
@@ -30,13 +41,19 @@ pub struct CreateUnsignedTransactionRequest {
     pub tracking_id: String,
     /// Whether to swap token X to token Y.
     pub is_swap_x_to_y: bool,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
 }
 
 impl CreateUnsignedTransactionRequest {
     pub fn builder(
-        user_address: &str,
-        token_mint_x: &str,
-        token_mint_y: &str,
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
         amount_in: u64,
         min_out: u64,
     ) -> CreateUnsignedTransactionRequestBuilder {
@@ -50,19 +67,81 @@ impl CreateUnsignedTransactionRequest {
     }
 }
 
+/// The maximum age of the quote a request is built from (30 seconds by default).
+const DEFAULT_MAX_QUOTE_AGE: Duration = Duration::from_secs(30);
+
+/// One hundred percent, in basis points.
+const MAX_SLIPPAGE_BPS: u16 = 10_000;
+
+impl CreateUnsignedTransactionRequest {
+    /// Creates a new `CreateUnsignedTransactionRequestBuilder` for the swap of a quote.
+    ///
+    /// The mints, the direction and `amount_in` are taken from the quote, and `min_out` is the
+    /// `amount_out` of the quote minus `slippage_bps` basis points, rounded down.
+    /// The builder rejects the quote if it is older than 30 seconds, see `max_quote_age`.
+    pub fn from_quote(
+        quote: &QuoteResponse,
+        user_address: WalletAddress,
+        slippage_bps: u16,
+    ) -> CreateUnsignedTransactionRequestBuilder {
+        let mut builder = CreateUnsignedTransactionRequestBuilder::new(
+            user_address,
+            quote.token_mint_x,
+            quote.token_mint_y,
+            quote.amount_in,
+            min_out(quote.amount_out, slippage_bps),
+        )
+        .is_swap_x_to_y(quote.is_swap_x_to_y)
+        .expected_out(quote.amount_out);
+        builder.slippage_bps = Some(slippage_bps);
+        builder.quote_received_at = Some(quote.received_at);
+        builder
+    }
+}
+
+/// Returns the minimum output of a swap given the expected output and the slippage tolerance.
+fn min_out(amount_out: u64, slippage_bps: u16) -> u64 {
+    let kept_bps = MAX_SLIPPAGE_BPS.saturating_sub(slippage_bps);
+    // The product of a u64 and a u16 always fits in a u128, and the result is at most amount_out.
+    (amount_out as u128 * kept_bps as u128 / MAX_SLIPPAGE_BPS as u128) as u64
+}
+
+/// Validate implementation for the unsigned transaction request.
+///
+/// The rules of `api.proto` are checked first, then the mints must be distinct and a priority
+/// fee cannot be combined with a priority level.
+impl Validate for CreateUnsignedTransactionRequest {
+    fn validate(&self) -> Result<()> {
+        self.validator().finish()
+    }
+}
+
+impl CreateUnsignedTransactionRequest {
+    /// Returns the validator holding the errors of every field of the request.
+    fn validator(&self) -> Validator {
+        ProtoCreateUnsignedTransactionRequest::from(self.clone())
+            .check_rules(Validator::new())
+            .distinct_mints(&self.token_mint_x, &self.token_mint_y)
+            .priority(self.priority_fee_micro_lamports, self.priority_level)
+    }
+}
+
 /// Convert from CreateUnsignedTransactionRequest to ProtoCreateUnsignedTransactionRequest
 ///
 /// This function is used to convert from CreateUnsignedTransactionRequest to ProtoCreateUnsignedTransactionRequest.
 impl From<CreateUnsignedTransactionRequest> for ProtoCreateUnsignedTransactionRequest {
     fn from(request: CreateUnsignedTransactionRequest) -> Self {
         Self {
-            user_address: request.user_address,
-            token_mint_x: request.token_mint_x,
-            token_mint_y: request.token_mint_y,
+            user_address: request.user_address.to_string(),
+            token_mint_x: request.token_mint_x.to_string(),
+            token_mint_y: request.token_mint_y.to_string(),
             amount_in: request.amount_in,
             min_out: request.min_out,
             tracking_id: request.tracking_id,
             is_swap_x_to_y: request.is_swap_x_to_y,
+            priority_fee_micro_lamports: request.priority_fee_micro_lamports,
+            compute_unit_limit: request.compute_unit_limit,
+            priority_level: PriorityLevel::to_proto(request.priority_level),
         }
     }
 }
@@ -78,6 +157,36 @@ pub struct CreateUnsignedTransactionResponse {
     pub order_id: String,
     /// The trade id.
     pub trade_id: String,
+    /// When the response was received.
+    pub received_at: Instant,
+    /// The compute unit price chosen by the service, in micro lamports.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit chosen by the service.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the compute unit price was resolved from.
+    pub priority_level: Option<PriorityLevel>,
+}
+
+/// The estimated validity of the recent blockhash of an unsigned transaction.
+///
+/// A blockhash is valid for 150 blocks, about 60 seconds at 400 milliseconds per slot. The estimate
+/// is measured from the response, while the service fetched the blockhash earlier and slots may be
+/// shorter, so a safety margin of 15 seconds is subtracted to expire the transaction early rather
+/// than late.
+pub const BLOCKHASH_VALIDITY: Duration = Duration::from_secs(45);
+
+impl CreateUnsignedTransactionResponse {
+    /// Returns the estimated time the recent blockhash of the transaction expires, after which
+    /// it can no longer be sent. See `BLOCKHASH_VALIDITY`.
+    pub fn blockhash_expires_at(&self) -> Instant {
+        self.received_at + BLOCKHASH_VALIDITY
+    }
+
+    /// Whether the recent blockhash of the transaction is estimated to be expired, in which case
+    /// it should be re-created with `Client::refresh_unsigned_transaction` before signing.
+    pub fn is_blockhash_expired(&self) -> bool {
+        self.received_at.elapsed() >= BLOCKHASH_VALIDITY
+    }
 }
 
 /// Convert from ProtoCreateUnsignedTransactionResponse to CreateUnsignedTransactionResponse
@@ -89,6 +198,10 @@ impl From<ProtoCreateUnsignedTransactionResponse> for CreateUnsignedTransactionR
             unsigned_transaction: response.unsigned_transaction,
             order_id: response.order_id,
             trade_id: response.trade_id,
+            received_at: Instant::now(),
+            priority_fee_micro_lamports: response.priority_fee_micro_lamports,
+            compute_unit_limit: response.compute_unit_limit,
+            priority_level: PriorityLevel::from_proto(response.priority_level),
         }
     }
 }
@@ -96,11 +209,11 @@ impl From<ProtoCreateUnsignedTransactionResponse> for CreateUnsignedTransactionR
 #[derive(Debug, Clone)]
 pub struct CreateUnsignedTransactionRequestBuilder {
     /// The wallet address of the user.
-    pub user_address: String,
+    pub user_address: WalletAddress,
     /// The mint address of the token X.
-    pub token_mint_x: String,
+    pub token_mint_x: Mint,
     /// The mint address of the token Y.
-    pub token_mint_y: String,
+    pub token_mint_y: Mint,
     /// The amount of token to swap.
     pub amount_in: u64,
     /// The minimum amount of token to receive.
@@ -109,24 +222,46 @@ pub struct CreateUnsignedTransactionRequestBuilder {
     pub tracking_id: Option<String>,
     /// Whether to swap token X to token Y.
     pub is_swap_x_to_y: Option<bool>,
+    /// The expected amount of token to receive, e.g. the `amount_out` of a quote.
+    /// It is only used to check `min_out` and is not sent.
+    pub expected_out: Option<u64>,
+    /// The slippage tolerance `min_out` was computed with, in basis points.
+    pub slippage_bps: Option<u16>,
+    /// When the quote the request is built from was received.
+    pub quote_received_at: Option<Instant>,
+    /// The maximum age of the quote the request is built from.
+    pub max_quote_age: Duration,
+    /// The compute unit price, in micro lamports. Cannot be combined with `priority_level`.
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// The compute unit limit, 1 400 000 max.
+    pub compute_unit_limit: Option<u32>,
+    /// The priority level the service resolves into a compute unit price.
+    pub priority_level: Option<PriorityLevel>,
 }
 
 impl CreateUnsignedTransactionRequestBuilder {
     pub fn new(
-        user_address: &str,
-        token_mint_x: &str,
-        token_mint_y: &str,
+        user_address: WalletAddress,
+        token_mint_x: Mint,
+        token_mint_y: Mint,
         amount_in: u64,
         min_out: u64,
     ) -> Self {
         Self {
-            user_address: user_address.to_string(),
-            token_mint_x: token_mint_x.to_string(),
-            token_mint_y: token_mint_y.to_string(),
-            amount_in: amount_in,
-            min_out: min_out,
+            user_address,
+            token_mint_x,
+            token_mint_y,
+            amount_in,
+            min_out,
             tracking_id: None,
             is_swap_x_to_y: None,
+            expected_out: None,
+            slippage_bps: None,
+            quote_received_at: None,
+            max_quote_age: DEFAULT_MAX_QUOTE_AGE,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            priority_level: None,
         }
     }
 
@@ -140,27 +275,214 @@ impl CreateUnsignedTransactionRequestBuilder {
         self
     }
 
-    pub fn build(self) -> CreateUnsignedTransactionRequest {
-        let tracking_id: String;
-        if self.tracking_id.is_none() {
-            tracking_id = Alphanumeric.sample_string(&mut rand::rng(), 12);
-        } else {
-            tracking_id = self.tracking_id.unwrap();
-        }
-        let is_swap_x_to_y: bool;
-        if self.is_swap_x_to_y.is_none() {
-            is_swap_x_to_y = true;
-        } else {
-            is_swap_x_to_y = self.is_swap_x_to_y.unwrap();
+    /// Sets the expected amount of token to receive. `min_out` must not exceed it.
+    pub fn expected_out(mut self, expected_out: u64) -> Self {
+        self.expected_out = Some(expected_out);
+        self
+    }
+
+    /// Sets the maximum age of the quote the request is built from (30 seconds by default).
+    pub fn max_quote_age(mut self, max_quote_age: Duration) -> Self {
+        self.max_quote_age = max_quote_age;
+        self
+    }
+
+    /// Sets the compute unit price, in micro lamports.
+    pub fn priority_fee_micro_lamports(mut self, priority_fee_micro_lamports: u64) -> Self {
+        self.priority_fee_micro_lamports = Some(priority_fee_micro_lamports);
+        self
+    }
+
+    /// Sets the compute unit limit.
+    pub fn compute_unit_limit(mut self, compute_unit_limit: u32) -> Self {
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self
+    }
+
+    /// Sets the priority level the service resolves into a compute unit price.
+    pub fn priority_level(mut self, priority_level: PriorityLevel) -> Self {
+        self.priority_level = Some(priority_level);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::StaleQuote` if the request is built from a quote older than the maximum age.
+    /// Returns `Error::Validation` with every offending field if the request is invalid.
+    pub fn build(self) -> Result<CreateUnsignedTransactionRequest> {
+        if let Some(received_at) = self.quote_received_at {
+            let age = received_at.elapsed();
+            if age > self.max_quote_age {
+                return Err(Error::StaleQuote {
+                    age,
+                    max_age: self.max_quote_age,
+                });
+            }
         }
-        CreateUnsignedTransactionRequest {
+        let tracking_id = self
+            .tracking_id
+            .unwrap_or_else(|| Alphanumeric.sample_string(&mut rand::rng(), 12));
+        let is_swap_x_to_y = self.is_swap_x_to_y.unwrap_or(true);
+        let request = CreateUnsignedTransactionRequest {
             user_address: self.user_address,
             token_mint_x: self.token_mint_x,
             token_mint_y: self.token_mint_y,
             amount_in: self.amount_in,
             min_out: self.min_out,
-            tracking_id: tracking_id,
-            is_swap_x_to_y: is_swap_x_to_y,
+            tracking_id,
+            is_swap_x_to_y,
+            priority_fee_micro_lamports: self.priority_fee_micro_lamports,
+            compute_unit_limit: self.compute_unit_limit,
+            priority_level: self.priority_level,
+        };
+        let mut validator = request.validator();
+        if let Some(expected_out) = self.expected_out {
+            validator = validator.at_most("min_out", request.min_out, "expected_out", expected_out);
+        }
+        if let Some(slippage_bps) = self.slippage_bps {
+            validator = validator.lte("slippage_bps", slippage_bps, MAX_SLIPPAGE_BPS);
+        }
+        validator.finish()?;
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = "4bRZeVcTPFTFwcL8hgbEX3gdzQ37v2vr2GSmvR7X4Asp";
+    const MINT_X: &str = "DdLxrGFs2sKYbbqVk76eVx9268ASUdTMAhrsqphqDuX";
+    const MINT_Y: &str = "HXsKnhXPtGr2mq4uTpxbxyy7ZydYWJwx4zMuYPEDukY";
+
+    #[test]
+    /// Test builder validation
+    ///
+    /// This is used to test that the builder accepts a valid request and reports every
+    /// offending field of an invalid one.
+    fn test_builder_validates_request() {
+        assert!(
+            CreateUnsignedTransactionRequest::builder(
+                USER.parse().unwrap(),
+                MINT_X.parse().unwrap(),
+                MINT_Y.parse().unwrap(),
+                1000,
+                900
+            )
+            .expected_out(950)
+            .build()
+            .is_ok()
+        );
+
+        let mint_x = MINT_X.parse().unwrap();
+        let error = CreateUnsignedTransactionRequest::builder(
+            USER.parse().unwrap(),
+            mint_x,
+            mint_x,
+            0,
+            900,
+        )
+        .expected_out(800)
+        .build()
+        .unwrap_err();
+
+        let fields: Vec<&str> = error
+            .field_errors()
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["amount_in", "token_mint_y", "min_out"]);
+    }
+
+    fn quote(received_at: Instant) -> QuoteResponse {
+        QuoteResponse {
+            token_mint_x: MINT_X.parse().unwrap(),
+            token_mint_y: MINT_Y.parse().unwrap(),
+            amount_in: 1_000,
+            amount_out: 999_999,
+            fee_amount: 0,
+            fee_pct: 0.0,
+            is_swap_x_to_y: false,
+            received_at,
         }
     }
+
+    #[test]
+    /// Test request from a quote
+    ///
+    /// This is used to test that `min_out` is rounded down, that the quote direction is kept and
+    /// that stale quotes and out of range slippages are rejected.
+    fn test_from_quote() {
+        let user = USER.parse().unwrap();
+
+        let request =
+            CreateUnsignedTransactionRequest::from_quote(&quote(Instant::now()), user, 50)
+                .build()
+                .unwrap();
+        assert_eq!(request.min_out, 994_999);
+        assert_eq!(request.amount_in, 1_000);
+        assert!(!request.is_swap_x_to_y);
+        assert_eq!(min_out(u64::MAX, 0), u64::MAX);
+        assert_eq!(min_out(999_999, 10_000), 0);
+
+        let old = Instant::now() - Duration::from_secs(60);
+        assert!(matches!(
+            CreateUnsignedTransactionRequest::from_quote(&quote(old), user, 50).build(),
+            Err(Error::StaleQuote { .. })
+        ));
+        assert!(
+            CreateUnsignedTransactionRequest::from_quote(&quote(old), user, 50)
+                .max_quote_age(Duration::from_secs(120))
+                .build()
+                .is_ok()
+        );
+
+        let error =
+            CreateUnsignedTransactionRequest::from_quote(&quote(Instant::now()), user, 10_001)
+                .build()
+                .unwrap_err();
+        assert_eq!(error.field_errors()[0].field, "slippage_bps");
+    }
+
+    #[test]
+    /// Test priority options
+    ///
+    /// This is used to test that the compute unit limit is bounded, that a priority fee cannot
+    /// be combined with a priority level, and that the options are sent in the proto request.
+    fn test_priority_options() {
+        let builder = CreateUnsignedTransactionRequest::builder(
+            USER.parse().unwrap(),
+            MINT_X.parse().unwrap(),
+            MINT_Y.parse().unwrap(),
+            1000,
+            900,
+        );
+
+        let request = builder
+            .clone()
+            .priority_level(PriorityLevel::Auto)
+            .compute_unit_limit(200_000)
+            .build()
+            .unwrap();
+        let proto = ProtoCreateUnsignedTransactionRequest::from(request);
+        assert_eq!(proto.compute_unit_limit, Some(200_000));
+        assert_eq!(proto.priority_fee_micro_lamports, None);
+        assert_eq!(
+            PriorityLevel::from_proto(proto.priority_level),
+            Some(PriorityLevel::Auto)
+        );
+
+        let Err(Error::Validation(errors)) = builder
+            .priority_level(PriorityLevel::High)
+            .priority_fee_micro_lamports(10_000)
+            .compute_unit_limit(1_400_001)
+            .build()
+        else {
+            panic!("expected a validation error");
+        };
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(fields, ["compute_unit_limit", "priority_level"]);
+    }
 }
@@ -0,0 +1,266 @@
+// (c) Darklake Labs PTE Ltd.
+//
+// Use of this source code is governed by an MIT-style
+// license that can be found in the LICENSE file or at
+// https://opensource.org/licenses/MIT.
+
+use std::fmt::Display;
+
+use regex::Regex;
+
+use crate::{
+    error::{Error, FieldError, Result},
+    models::{address::Mint, enums::PriorityLevel},
+};
+
+/// Validate
+///
+/// This trait is used to check a request on the client side, before it is sent. The builders
+/// and the client run it, so an invalid request never reaches the Darklake Integrations service.
+pub trait Validate {
+    /// Checks every field of the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` with every offending field if the request is invalid.
+    fn validate(&self) -> Result<()>;
+}
+
+/// Proto rules
+///
+/// This trait is implemented by the API messages annotated with protoc-gen-validate rules in
+/// `api.proto`. The implementations are generated by the build script, so the client checks the
+/// same declarative contract as the server.
+pub(crate) trait ProtoRules {
+    /// Checks the rules of every annotated field.
+    fn check_rules(&self, validator: Validator) -> Validator;
+}
+
+include!(concat!(env!("OUT_DIR"), "/darklake.v1.rules.rs"));
+
+/// Validator
+///
+/// This struct is used to collect the errors of every field of a request, so they are all
+/// reported at once. Only the first error of a field is kept.
+#[derive(Debug, Default)]
+pub(crate) struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an error for the field.
+    pub(crate) fn error(mut self, field: &str, message: &str) -> Self {
+        if self.errors.iter().any(|error| error.field == field) {
+            return self;
+        }
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+        self
+    }
+
+    /// Checks that the two mints of a pair differ.
+    pub(crate) fn distinct_mints(self, token_mint_x: &Mint, token_mint_y: &Mint) -> Self {
+        if token_mint_x != token_mint_y {
+            self
+        } else {
+            self.error("token_mint_y", "must differ from token_mint_x")
+        }
+    }
+
+    /// Checks that the value is greater than the bound.
+    pub(crate) fn gt<T: PartialOrd + Display>(self, field: &str, value: T, bound: T) -> Self {
+        if value > bound {
+            self
+        } else {
+            self.error(field, &format!("must be greater than {}", bound))
+        }
+    }
+
+    /// Checks that the value is greater than or equal to the bound.
+    pub(crate) fn gte<T: PartialOrd + Display>(self, field: &str, value: T, bound: T) -> Self {
+        if value >= bound {
+            self
+        } else {
+            self.error(field, &format!("must be at least {}", bound))
+        }
+    }
+
+    /// Checks that the value is less than the bound.
+    ///
+    /// It is emitted by the build script for the `lt` rules, which no field of api.proto has yet.
+    #[allow(dead_code)]
+    pub(crate) fn lt<T: PartialOrd + Display>(self, field: &str, value: T, bound: T) -> Self {
+        if value < bound {
+            self
+        } else {
+            self.error(field, &format!("must be less than {}", bound))
+        }
+    }
+
+    /// Checks that the value is less than or equal to the bound.
+    pub(crate) fn lte<T: PartialOrd + Display>(self, field: &str, value: T, bound: T) -> Self {
+        if value <= bound {
+            self
+        } else {
+            self.error(field, &format!("must be at most {}", bound))
+        }
+    }
+
+    /// Runs the check on the value of an optional field, if it is set.
+    pub(crate) fn optional<T>(self, value: Option<T>, check: impl FnOnce(Self, T) -> Self) -> Self {
+        match value {
+            Some(value) => check(self, value),
+            None => self,
+        }
+    }
+
+    /// Checks that a priority fee and a priority level are not both requested, since the level
+    /// is resolved by the service into a fee.
+    pub(crate) fn priority(
+        self,
+        priority_fee_micro_lamports: Option<u64>,
+        priority_level: Option<PriorityLevel>,
+    ) -> Self {
+        if priority_fee_micro_lamports.is_some() && priority_level.is_some() {
+            self.error(
+                "priority_level",
+                "must not be set with priority_fee_micro_lamports",
+            )
+        } else {
+            self
+        }
+    }
+
+    /// Checks that the text is at least `min` characters long.
+    pub(crate) fn min_len(self, field: &str, value: &str, min: usize) -> Self {
+        if value.chars().count() >= min {
+            self
+        } else {
+            self.error(field, &format!("must be at least {} characters long", min))
+        }
+    }
+
+    /// Checks that the text matches the pattern.
+    pub(crate) fn pattern(self, field: &str, value: &str, pattern: &Regex) -> Self {
+        if pattern.is_match(value) {
+            self
+        } else {
+            self.error(field, &format!("must match {}", pattern.as_str()))
+        }
+    }
+
+    /// Checks that the value does not exceed the bound.
+    pub(crate) fn at_most(self, field: &str, value: u64, bound: &str, max: u64) -> Self {
+        if value <= max {
+            self
+        } else {
+            self.error(field, &format!("must not exceed {} ({})", bound, max))
+        }
+    }
+
+    /// Checks that the text is at most `max` characters long.
+    pub(crate) fn max_chars(self, field: &str, value: &str, max: usize) -> Self {
+        if value.chars().count() <= max {
+            self
+        } else {
+            self.error(field, &format!("must be at most {} characters long", max))
+        }
+    }
+
+    /// Checks that the list has at most `max` items.
+    pub(crate) fn max_items<T>(self, field: &str, values: &[T], max: usize) -> Self {
+        if values.len() <= max {
+            self
+        } else {
+            self.error(field, &format!("must have at most {} items", max))
+        }
+    }
+
+    /// Returns `Error::Validation` if any field is invalid.
+    pub(crate) fn finish(self) -> Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(self.errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Test validator
+    ///
+    /// This is used to test that every offending field is reported.
+    fn test_validator_reports_every_field() {
+        let mint: Mint = "So11111111111111111111111111111111111111112"
+            .parse()
+            .unwrap();
+        let error = Validator::new()
+            .min_len("trade_id", "", 1)
+            .distinct_mints(&mint, &mint)
+            .gt("amount_in", 0, 0)
+            .finish()
+            .unwrap_err();
+
+        let fields: Vec<&str> = error
+            .field_errors()
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["trade_id", "token_mint_y", "amount_in"]);
+    }
+
+    #[test]
+    /// Test proto rules
+    ///
+    /// This is used to test that the rules declared in `api.proto` are enforced, with one error
+    /// per field.
+    fn test_proto_rules() {
+        let request = |page_size| {
+            crate::models::GetTradesListByUserRequest::new(
+                "So11111111111111111111111111111111111111112"
+                    .parse()
+                    .unwrap(),
+                page_size,
+                -1,
+            )
+        };
+        assert_eq!(
+            request(100).validate().unwrap_err().field_errors(),
+            &[FieldError {
+                field: "page_number".to_string(),
+                message: "must be at least 0".to_string(),
+            }]
+        );
+
+        let error = request(101).validate().unwrap_err();
+        assert_eq!(error.field_errors()[0].field, "page_size");
+        assert_eq!(error.field_errors()[0].message, "must be at most 100");
+
+        let error = crate::models::SendSignedTransactionRequest::builder("", "trade")
+            .build()
+            .validate()
+            .unwrap_err();
+        assert_eq!(error.field_errors().len(), 1);
+        assert_eq!(error.field_errors()[0].field, "signed_transaction");
+
+        let trade_ids: Vec<&str> = vec!["trade"; 101];
+        let error = crate::models::CheckTradeStatusBatchRequest::new(&trade_ids)
+            .validate()
+            .unwrap_err();
+        assert_eq!(error.field_errors()[0].field, "trade_ids");
+        assert_eq!(
+            error.field_errors()[0].message,
+            "must have at most 100 items"
+        );
+    }
+}